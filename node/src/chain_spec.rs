@@ -1,5 +1,5 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, AssetsConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig, MigrationConfig,
+	AccountId, AuraConfig, CouncilConfig, BalancesConfig, AssetsConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig, Migration, MigrationConfig,
 	SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
@@ -162,7 +162,8 @@ fn testnet_genesis(
 			code: wasm_binary.to_vec(),
 		},
 		assets: AssetsConfig {
-			assets: vec!((TOKEN_ID, root_key.clone(), true, 1)),
+			// Owned by the migration pallet account, so nobody can mint or change its roles.
+			assets: vec!((TOKEN_ID, Migration::account_id(), true, 1)),
 			/// Genesis metadata: id, name, symbol, decimals
 			metadata: vec!((TOKEN_ID, "Jur token".as_bytes().to_vec(), "JUR".as_bytes().to_vec(), 12)),
 			/// Genesis accounts: id, account_id, balance
@@ -176,7 +177,15 @@ fn testnet_genesis(
 		migration: MigrationConfig {
 			migration_vault_account: Some(get_account_id_from_seed::<sr25519::Public>("MigrationVault")),
 			migration_owner: Some(get_account_id_from_seed::<sr25519::Public>("Alice")),
-			asset_id: Some(TOKEN_ID)
+			asset_id: Some(TOKEN_ID),
+			// Set to the token generation event block to keep migrated tokens locked until then.
+			transfer_lock_until: None,
+			// `(source block, state root)` of snapshots balances can be proven against.
+			source_state_roots: vec![],
+		},
+		council: CouncilConfig {
+			members: endowed_accounts.iter().take(3).cloned().collect(),
+			phantom: Default::default(),
 		},
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -189,5 +198,6 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		treasury: Default::default(),
 	}
 }
@@ -1,4 +1,5 @@
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +8,12 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// JSON file with migrations to add to the off-chain worker queue on startup.
+	///
+	/// The node must hold the migration owner key (key type `migr`) and run the off-chain worker.
+	#[arg(long, value_name = "PATH")]
+	pub migration_queue: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
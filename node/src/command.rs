@@ -195,8 +195,9 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let migration_queue = cli.migration_queue.clone();
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, migration_queue).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
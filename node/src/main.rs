@@ -7,6 +7,7 @@ mod service;
 mod cli;
 mod benchmarking;
 mod command;
+mod migration_queue;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
@@ -0,0 +1,87 @@
+//! Provisioning of the migration off-chain worker queue from a local file.
+
+use node_template_runtime::{
+	pallet_migration::PendingMigration,
+	AccountId, Balance, Migration, SourceAccountId,
+};
+use sp_core::{
+	bytes::from_hex,
+	crypto::Ss58Codec,
+	offchain::{OffchainStorage, STORAGE_PREFIX},
+	Decode, Encode,
+};
+use std::{fs::File, path::Path};
+
+type Item = PendingMigration<SourceAccountId, AccountId, Balance>;
+
+/// Read pending migrations from a JSON file.
+///
+/// The file holds an array of objects with `for_account` (hex, SCALE encoded), `account_to_credit`
+/// (SS58), `amount` (number or decimal string), `source_ref` (hex) and optionally `category`
+/// (the name of an allocation category).
+pub fn load(path: &Path) -> Result<Vec<Item>, String> {
+	let file = File::open(path).map_err(|e| format!("Error opening {}: {}", path.display(), e))?;
+	let entries: Vec<serde_json::Value> = serde_json::from_reader(file)
+		.map_err(|e| format!("Error parsing {}: {}", path.display(), e))?;
+
+	entries
+		.iter()
+		.enumerate()
+		.map(|(i, entry)| parse_entry(entry).map_err(|e| format!("Entry {}: {}", i, e)))
+		.collect()
+}
+
+fn parse_entry(entry: &serde_json::Value) -> Result<Item, String> {
+	let field = |name: &str| entry.get(name).ok_or_else(|| format!("missing `{}`", name));
+
+	let for_account = field("for_account")?
+		.as_str()
+		.and_then(|s| from_hex(s).ok())
+		.and_then(|bytes| {
+			let mut input = &bytes[..];
+			SourceAccountId::decode(&mut input).ok().filter(|_| input.is_empty())
+		})
+		.ok_or("`for_account` must be a hex encoded source account")?;
+	let account_to_credit = field("account_to_credit")?
+		.as_str()
+		.and_then(|s| AccountId::from_ss58check(s).ok())
+		.ok_or("`account_to_credit` must be an SS58 address")?;
+	let amount = match field("amount")? {
+		serde_json::Value::Number(n) => n.as_u64().map(Balance::from),
+		serde_json::Value::String(s) => s.parse::<Balance>().ok(),
+		_ => None,
+	}
+	.ok_or("`amount` must be a non-negative integer")?;
+	let source_ref = field("source_ref")?
+		.as_str()
+		.and_then(|s| from_hex(s).ok())
+		.filter(|bytes| !bytes.is_empty())
+		.ok_or("`source_ref` must be a non-empty hex string")?;
+	let category = match entry.get("category") {
+		None | Some(serde_json::Value::Null) => None,
+		Some(category) => Some(category.as_str().ok_or("`category` must be a string")?.as_bytes().to_vec()),
+	};
+
+	Ok(PendingMigration { for_account, account_to_credit, amount, source_ref, category })
+}
+
+/// Append `items` to the queue in the node's offchain storage.
+///
+/// Items whose `source_ref` is already queued are skipped. Returns the number of items added.
+pub fn provision<S: OffchainStorage>(storage: &mut S, items: Vec<Item>) -> usize {
+	let queue_key = Migration::offchain_queue_key();
+	let mut queue: Vec<Item> = storage
+		.get(STORAGE_PREFIX, &queue_key)
+		.and_then(|raw| Decode::decode(&mut &raw[..]).ok())
+		.unwrap_or_default();
+
+	let before = queue.len();
+	for item in items {
+		if !queue.iter().any(|queued| queued.source_ref == item.source_ref) {
+			queue.push(item);
+		}
+	}
+	storage.set(STORAGE_PREFIX, &queue_key, &queue.encode());
+
+	queue.len() - before
+}
@@ -1,7 +1,7 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::BlockBackend;
+use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
@@ -9,7 +9,7 @@ use sc_keystore::LocalKeystore;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -155,7 +155,10 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	migration_queue: Option<PathBuf>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -203,6 +206,15 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			warp_sync: Some(warp_sync),
 		})?;
 
+	if let Some(path) = migration_queue {
+		let items = crate::migration_queue::load(&path).map_err(ServiceError::Other)?;
+		let mut storage = backend
+			.offchain_storage()
+			.ok_or_else(|| ServiceError::Other("Offchain storage is not available.".into()))?;
+		let added = crate::migration_queue::provision(&mut storage, items);
+		log::info!("Added {} migrations from {} to the off-chain queue", added, path.display());
+	}
+
 	if config.offchain_worker.enabled {
 		sc_service::build_offchain_workers(
 			&config,
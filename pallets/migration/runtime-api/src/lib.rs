@@ -0,0 +1,42 @@
+//! Runtime API definition for the migration pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_migration::{CategoryTotals, MigrationDryRunOutcome, MigrationQueueProgress, MigrationRequest, OutboundRelease};
+
+sp_api::decl_runtime_apis! {
+	#[api_version(4)]
+	pub trait MigrationApi<AccountId, SourceAccountId, Balance>
+	where
+		AccountId: Codec,
+		SourceAccountId: Codec,
+		Balance: Codec,
+	{
+		/// Validate `migrations` as if `who` submitted them one after another, without
+		/// changing any state.
+		fn dry_run_migrate(
+			who: AccountId,
+			migrations: Vec<MigrationRequest<SourceAccountId, AccountId, Balance>>,
+		) -> Vec<MigrationDryRunOutcome<Balance>>;
+
+		/// Releases on the source chain requested with `migrate_back` that the relayer has not
+		/// confirmed yet, by nonce.
+		#[api_version(2)]
+		fn pending_releases() -> Vec<(u64, OutboundRelease<AccountId, SourceAccountId, Balance>)>;
+
+		/// Progress of the queue of migrations executed in `on_idle`.
+		#[api_version(3)]
+		fn migration_queue_progress() -> MigrationQueueProgress;
+
+		/// Queued migrations that failed when executed, by queue index.
+		#[api_version(3)]
+		fn failed_migrations() -> Vec<(u32, MigrationRequest<SourceAccountId, AccountId, Balance>)>;
+
+		/// Cap, migrated total and remaining sub-vault balance of every allocation category.
+		#[api_version(4)]
+		fn category_totals() -> Vec<CategoryTotals<AccountId, Balance>>;
+	}
+}
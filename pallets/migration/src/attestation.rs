@@ -0,0 +1,52 @@
+//! Migrations attested by a threshold of off-chain attesters.
+//!
+//! Attesters watch the source chain and sign each transfer to the migration with their key. Once
+//! enough of the current attester set signed, anyone can submit the signatures in an unsigned
+//! `migrate_attested` transaction. Signatures commit to the attester set id, so they are void once
+//! governance replaces the set.
+
+use crate::pallet::*;
+use codec::Encode;
+use frame_support::{ensure, traits::Get};
+use sp_runtime::RuntimeAppPublic;
+use sp_std::vec::Vec;
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Payload attesters of the current set sign to attest a migration.
+	pub fn attestation_payload(
+		source_ref: &SourceRef<T, I>,
+		for_account: &T::SourceAccountId,
+		account_to_credit: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		category: Option<&CategoryName<T, I>>,
+	) -> Vec<u8> {
+		(b"jur/attest", T::PalletId::get(), <AttesterSetId<T, I>>::get(), source_ref, for_account, account_to_credit, amount, category)
+			.encode()
+	}
+
+	/// Check that `attestations` hold valid signatures of the migration by at least the
+	/// threshold of distinct attesters of the current set.
+	pub(crate) fn check_attestations(
+		source_ref: &SourceRef<T, I>,
+		for_account: &T::SourceAccountId,
+		account_to_credit: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		category: Option<&CategoryName<T, I>>,
+		attestations: &AttestationsOf<T, I>,
+	) -> Result<(), Error<T, I>> {
+		let threshold = <AttesterThreshold<T, I>>::get();
+		ensure!(threshold > 0, Error::<T, I>::NoAttesters);
+
+		let attesters = <Attesters<T, I>>::get();
+		let payload = Self::attestation_payload(source_ref, for_account, account_to_credit, amount, category);
+		let mut signers = Vec::with_capacity(attestations.len());
+		for (index, signature) in attestations.iter() {
+			let attester = attesters.get(*index as usize).ok_or(Error::<T, I>::InvalidAttestation)?;
+			ensure!(!signers.contains(index), Error::<T, I>::InvalidAttestation);
+			ensure!(attester.verify(&payload, signature), Error::<T, I>::InvalidAttestation);
+			signers.push(*index);
+		}
+		ensure!(signers.len() as u32 >= threshold, Error::<T, I>::InsufficientAttestations);
+		Ok(())
+	}
+}
@@ -4,22 +4,46 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Migration;
-use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use crate::pallet::BalanceOf;
+use frame_benchmarking::{benchmarks_instance_pallet, account, whitelisted_caller, BenchmarkError};
 use frame_system::RawOrigin;
+use frame_support::dispatch::Pays;
 use frame_support::inherent::Vec;
-use sp_core::Encode;
-use frame_support::traits::fungibles::{Create, Mutate};
+use sp_std::vec;
+use frame_support::traits::{Currency, EnsureOrigin, Get, Hooks, ReservableCurrency, fungibles::{Create, Mutate}};
+use frame_support::sp_runtime::FixedU128;
+use crate::storage_proof::{build_source_state, source_account_info};
+use crate::light_client::{build_justification, source_events_entry};
+use crate::ethereum::{build_receipts_trie, erc20_burn_receipt, erc20_burns_receipt, ethereum_header};
+use codec::Encode;
+use sp_core::{H160, H256};
+use sp_finality_grandpa::AuthorityId as SourceAuthorityId;
+use sp_runtime::{traits::{Hash as HashT, Header as HeaderT, Zero}, RuntimeAppPublic};
 
 const SEED: u32 = 0;
 const VAULT_INDEX: u32 = 4;
 const TOKEN_ID: u32 = 1;
 
-fn default_asset_id<T: Config>() -> T::AssetIdParameter {
+fn source_header(number: u32, parent_hash: H256) -> SourceHeader {
+	SourceHeader::new(number, Default::default(), H256::repeat_byte(number as u8), parent_hash, Default::default())
+}
+
+fn attesters<T: Config<I>, I: 'static>(count: u32) -> AttestersOf<T, I> {
+	let mut attesters = (0..count).map(|_| T::AttesterId::generate_pair(None)).collect::<Vec<_>>();
+	attesters.sort();
+	attesters.try_into().unwrap()
+}
+
+fn source_authorities(count: u32) -> Vec<SourceAuthorityId> {
+	(0..count).map(|_| SourceAuthorityId::generate_pair(None)).collect()
+}
+
+fn default_asset_id<T: Config<I>, I: 'static>() -> T::AssetIdParameter {
 	T::BenchmarkHelper::create_asset_id_parameter(TOKEN_ID)
 }
 
-fn create_default_asset<T: Config>() -> (T::AssetIdParameter, T::AccountId) {
-	let asset_id = default_asset_id::<T>();
+fn create_default_asset<T: Config<I>, I: 'static>() -> (T::AssetIdParameter, T::AccountId) {
+	let asset_id = default_asset_id::<T, I>();
 	let caller: T::AccountId = whitelisted_caller();
 	T::Assets::create(
 		asset_id.into(),
@@ -30,11 +54,11 @@ fn create_default_asset<T: Config>() -> (T::AssetIdParameter, T::AccountId) {
 	(asset_id, caller.clone())
 }
 
-fn create_default_minted_asset<T: Config>(
+fn create_default_minted_asset<T: Config<I>, I: 'static>(
 	who: T::AccountId,
 	amount: u32,
 ) {
-	let (asset_id, _owner) = create_default_asset::<T>();
+	let (asset_id, _owner) = create_default_asset::<T, I>();
 
 	assert!(T::Assets::mint_into(
 		asset_id.into(),
@@ -44,20 +68,30 @@ fn create_default_minted_asset<T: Config>(
 	.is_ok());
 }
 
-fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+/// Category with vesting and a sub-vault holding `cap`, the most expensive one to migrate from.
+fn vesting_category<T: Config<I>, I: 'static>(cap: u32) -> (CategoryName<T, I>, T::AccountId) {
+	let category: CategoryName<T, I> = vec![b'c'; T::MaxCategoryNameLength::get() as usize].try_into().unwrap();
+	let category_vault = Migration::<T, I>::category_vault(&category);
+	<Categories<T, I>>::insert(&category, AllocationCategory {
+		vault: category_vault.clone(),
+		cap: cap.into(),
+		migrated: Zero::zero(),
+		vesting: Some(CategoryVesting { start: 10u32.into(), duration: 100u32.into() }),
+	});
+	<CategoryVaults<T, I>>::insert(&category_vault, ());
+	create_default_minted_asset::<T, I>(category_vault.clone(), cap);
+	(category, category_vault)
+}
+
+fn assert_last_event<T: Config<I>, I: 'static>(generic_event: <T as Config<I>>::RuntimeEvent) {
 	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
 }
 
-fn account_to_bytes<AccountId>(account: &AccountId) -> [u8; 32]
-	where AccountId: Encode,
-{
-	let account_vec = account.encode();
-	let mut bytes = [0u8; 32];
-	bytes.copy_from_slice(&account_vec);
-	bytes
+fn source_account<T: Config<I>, I: 'static>(index: u32) -> T::SourceAccountId {
+	T::BenchmarkHelper::create_source_account(index)
 }
 
-benchmarks! {
+benchmarks_instance_pallet! {
 	migrate {
 		let caller: T::AccountId = whitelisted_caller();
 
@@ -76,26 +110,523 @@ benchmarks! {
 		let vault_total = 1_000_000;
 		let migrate_amount = 100;
 
-		let for_account = account_to_bytes(&account2);
+		let for_account = source_account::<T, I>(2);
+		let source_ref: SourceRef<T, I> = vec![0u8; T::MaxSourceRefLength::get() as usize].try_into().unwrap();
 
-		<MigrationVaultAccount<T>>::put(migration_vault_account.clone());
-		<MigrationOwner<T>>::put(caller.clone());
-		<TokenId<T>>::put(default_asset_id::<T>().into());
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<MigrationOwner<T, I>>::put(caller.clone());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
 
-		create_default_minted_asset::<T>(migration_vault_account.clone(), vault_total);
-	}: _(RawOrigin::Signed(caller.clone()), for_account, account3.clone(), migrate_amount.into())
+		// Migrating from a category with vesting out of its own sub-vault.
+		let (category, category_vault) = vesting_category::<T, I>(vault_total);
+
+		// Enforcing a sealed allocation table is the most expensive path.
+		let allocation: BalanceOf<T, I> = vault_total.into();
+		<AllocationCommitment<T, I>>::put(AllocationCommitmentInfo {
+			root: Migration::<T, I>::allocation_commitment(&[(for_account.clone(), allocation)]),
+			entries: 1,
+		});
+		<Allocations<T, I>>::insert(&for_account, allocation);
+		<AllocationsSealed<T, I>>::put(true);
+	}: {
+		let post_info = Migration::<T, I>::migrate(
+			RawOrigin::Signed(caller.clone()).into(),
+			for_account.clone(),
+			account3.clone(),
+			migrate_amount.into(),
+			Some(source_ref.clone()),
+			Some(category.clone()),
+		).map_err(|e| e.error)?;
+		assert_eq!(post_info.pays_fee, Pays::No);
+	}
 	verify {
-		assert_last_event::<T>(
+		assert_last_event::<T, I>(
 			Event::BalanceMigrated { 
 				amount: migrate_amount.into(),
-				from_vault: migration_vault_account,
+				from_vault: category_vault,
 				for_account: for_account,
 				to_account: account3.clone().into(),
 				vault_balance_remained: (vault_total - migrate_amount).into(),
-				account_balance_after: migrate_amount.into()
+				account_balance_after: migrate_amount.into(),
+				source_ref: Some(source_ref)
 			}.into()
 		);
 	}
 
+	commit_allocations {
+		let caller: T::AccountId = whitelisted_caller();
+		<MigrationOwner<T, I>>::put(caller.clone());
+		let root = T::Hash::default();
+	}: _(RawOrigin::Signed(caller), root, T::MaxAllocationChunk::get())
+	verify {
+		assert!(<AllocationCommitment<T, I>>::exists());
+	}
+
+	upload_allocations {
+		let n in 1 .. T::MaxAllocationChunk::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		<MigrationOwner<T, I>>::put(caller.clone());
+
+		let entries: Vec<(T::SourceAccountId, BalanceOf<T, I>)> = (0..n)
+			.map(|i| (source_account::<T, I>(i), 100u32.into()))
+			.collect();
+		<AllocationCommitment<T, I>>::put(AllocationCommitmentInfo {
+			root: Migration::<T, I>::allocation_commitment(&entries),
+			entries: n,
+		});
+		let chunk: AllocationChunk<T, I> = entries.try_into().unwrap();
+	}: _(RawOrigin::Signed(caller), chunk)
+	verify {
+		assert_eq!(Migration::<T, I>::allocation_upload_progress().uploaded, n);
+	}
+
+	seal_allocations {
+		let caller: T::AccountId = whitelisted_caller();
+		<MigrationOwner<T, I>>::put(caller.clone());
+
+		let entries: Vec<(T::SourceAccountId, BalanceOf<T, I>)> = (0..T::MaxAllocationChunk::get())
+			.map(|i| (source_account::<T, I>(i), 100u32.into()))
+			.collect();
+		let root = Migration::<T, I>::allocation_commitment(&entries);
+		<AllocationCommitment<T, I>>::put(AllocationCommitmentInfo { root, entries: entries.len() as u32 });
+		<AllocationUpload<T, I>>::put(AllocationUploadProgress { accumulator: root, uploaded: entries.len() as u32 });
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert!(Migration::<T, I>::allocations_sealed());
+	}
+
+	set_transfer_lock {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let until: T::BlockNumber = 1_000u32.into();
+	}: _<T::RuntimeOrigin>(origin, Some(until))
+	verify {
+		assert_eq!(Migration::<T, I>::transfer_lock_until(), Some(until));
+	}
+
+	lift_transfer_lock {
+		let until: T::BlockNumber = 1u32.into();
+		<TransferLockUntil<T, I>>::put(until);
+	}: {
+		Migration::<T, I>::on_initialize(until);
+	}
+	verify {
+		assert!(!<TransferLockUntil<T, I>>::exists());
+	}
+
+	set_fee_rate {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let rate = FixedU128::from_rational(3, 2);
+	}: _<T::RuntimeOrigin>(origin, Some(rate))
+	verify {
+		assert_eq!(Migration::<T, I>::fee_rate(), Some(rate));
+	}
+
+	add_sponsor {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let sponsor = account::<T::AccountId>("Sponsor", 5, SEED);
+	}: _<T::RuntimeOrigin>(origin, sponsor.clone())
+	verify {
+		assert!(<Sponsors<T, I>>::contains_key(&sponsor));
+	}
+
+	remove_sponsor {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let sponsor = account::<T::AccountId>("Sponsor", 5, SEED);
+		<Sponsors<T, I>>::insert(&sponsor, ());
+	}: _<T::RuntimeOrigin>(origin, sponsor.clone())
+	verify {
+		assert!(!<Sponsors<T, I>>::contains_key(&sponsor));
+	}
+
+	sponsor {
+		let sponsor = account::<T::AccountId>("Sponsor", 5, SEED);
+		let who = account::<T::AccountId>("Recipient", 6, SEED);
+		<Sponsors<T, I>>::insert(&sponsor, ());
+		<MigrationRecipients<T, I>>::insert(&who, ());
+		let cap: BalanceOf<T, I> = 1_000_000u32.into();
+		let pallets: SponsoredPallets<T, I> = vec![0u8; T::MaxSponsoredPallets::get() as usize].try_into().unwrap();
+	}: _(RawOrigin::Signed(sponsor.clone()), who.clone(), cap, pallets)
+	verify {
+		assert_eq!(Migration::<T, I>::sponsorship(&who).map(|s| s.sponsor), Some(sponsor));
+	}
+
+	unsponsor {
+		let sponsor = account::<T::AccountId>("Sponsor", 5, SEED);
+		let who = account::<T::AccountId>("Recipient", 6, SEED);
+		<Sponsorships<T, I>>::insert(&who, Sponsorship {
+			sponsor: sponsor.clone(),
+			remaining: 1_000_000u32.into(),
+			pallets: Default::default(),
+		});
+	}: _(RawOrigin::Signed(sponsor), who.clone())
+	verify {
+		assert!(Migration::<T, I>::sponsorship(&who).is_none());
+	}
+
+	set_source_state_root {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let root = H256::repeat_byte(1);
+	}: _<T::RuntimeOrigin>(origin, 1_000, Some(root))
+	verify {
+		assert_eq!(Migration::<T, I>::source_state_root(1_000), Some(root));
+	}
+
+	claim_with_storage_proof {
+		// Unused nodes in the proof still have to be hashed.
+		let n in 1 .. T::MaxProofNodes::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let migration_vault_account = account::<T::AccountId>("MigrationVault", VAULT_INDEX, SEED);
+		let account_to_credit = account::<T::AccountId>("Bob", 2, SEED);
+		let for_account = source_account::<T, I>(2);
+		let vault_total = 1_000_000;
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<MigrationOwner<T, I>>::put(caller.clone());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		create_default_minted_asset::<T, I>(migration_vault_account, vault_total);
+
+		let balance = 100u128;
+		let value = match T::SourceBalanceEntry::get() {
+			SourceBalanceEntry::System => source_account_info(balance),
+			SourceBalanceEntry::Assets(_) => (balance, false).encode(),
+		};
+		let (root, mut proof) = build_source_state(&[(Migration::<T, I>::source_balance_key(&for_account), value)]);
+		proof.extend((1..n).map(|i| vec![i as u8; 512]));
+		let proof: StorageProofOf<T, I> = proof.try_into().unwrap();
+		<SourceStateRoots<T, I>>::insert(1_000, root);
+	}: _(RawOrigin::Signed(caller), 1_000, for_account.clone(), account_to_credit, proof)
+	verify {
+		assert_eq!(Migration::<T, I>::proven_claim(&for_account), Some(1_000));
+	}
+
+	initialize_light_client {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let header = source_header(0, H256::zero());
+		let authorities: SourceAuthoritiesOf<T, I> = source_authorities(T::MaxSourceAuthorities::get())
+			.into_iter()
+			.map(|id| (id, 1))
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+	}: _<T::RuntimeOrigin>(origin, header.clone(), 0, authorities)
+	verify {
+		assert_eq!(Migration::<T, I>::best_finalized_source_header(), Some((0, header.hash())));
+	}
+
+	submit_finality_proof {
+		// Every authority votes, so all their signatures have to be verified.
+		let p in 1 .. T::MaxSourceAuthorities::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let voters = source_authorities(p);
+		let authorities: SourceAuthoritiesOf<T, I> = voters.iter().cloned().map(|id| (id, 1)).collect::<Vec<_>>().try_into().unwrap();
+		let checkpoint = source_header(0, H256::zero());
+		Migration::<T, I>::initialize_light_client(origin, checkpoint.clone(), 0, authorities)?;
+
+		let header = source_header(1, checkpoint.hash());
+		let justification = build_justification(&header, 1, 0, &voters, |id, payload| id.sign(&payload).unwrap());
+	}: _(RawOrigin::Signed(caller), header.clone(), justification.encode())
+	verify {
+		assert_eq!(Migration::<T, I>::best_finalized_source_header(), Some((1, header.hash())));
+	}
+
+	claim_burn {
+		// Unused nodes in the proof still have to be hashed.
+		let n in 1 .. T::MaxProofNodes::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let migration_vault_account = account::<T::AccountId>("MigrationVault", VAULT_INDEX, SEED);
+		let account_to_credit = account::<T::AccountId>("Bob", 2, SEED);
+		let for_account = source_account::<T, I>(2);
+		let vault_total = 1_000_000;
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<MigrationOwner<T, I>>::put(caller.clone());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		create_default_minted_asset::<T, I>(migration_vault_account, vault_total);
+
+		let burn = T::SourceBurns::encode_burn(for_account, 100, account_to_credit).ok_or(BenchmarkError::Weightless)?;
+		let (root, mut proof) = build_source_state(&[source_events_entry(vec![burn])]);
+		proof.extend((1..n).map(|i| vec![i as u8; 512]));
+		let proof: StorageProofOf<T, I> = proof.try_into().unwrap();
+		<FinalizedSourceRoots<T, I>>::insert(1_000, root);
+	}: _(RawOrigin::Signed(caller), 1_000, 0, proof)
+	verify {
+		assert!(<ClaimedBurns<T, I>>::contains_key(1_000, 0));
+	}
+
+	add_relayer {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let relayer = account::<T::AccountId>("Relayer", 1, SEED);
+	}: _<T::RuntimeOrigin>(origin, relayer.clone())
+	verify {
+		assert!(<EthereumRelayers<T, I>>::contains_key(&relayer));
+	}
+
+	remove_relayer {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let relayer = account::<T::AccountId>("Relayer", 1, SEED);
+		<EthereumRelayers<T, I>>::insert(&relayer, ());
+	}: _<T::RuntimeOrigin>(origin, relayer.clone())
+	verify {
+		assert!(!<EthereumRelayers<T, I>>::contains_key(&relayer));
+	}
+
+	set_trusted_ethereum_block {
+		let relayer: T::AccountId = whitelisted_caller();
+		<EthereumRelayers<T, I>>::insert(&relayer, ());
+		let block_hash = H256::repeat_byte(1);
+	}: _(RawOrigin::Signed(relayer), block_hash, true)
+	verify {
+		assert!(<TrustedEthereumBlocks<T, I>>::contains_key(block_hash));
+	}
+
+	claim_erc20_burn {
+		// Unused nodes in the proof still have to be hashed.
+		let n in 1 .. T::MaxProofNodes::get();
+		// As many burns as fit in a receipt of about `r` bytes, which all have to be decoded.
+		let r in 1 .. T::MaxReceiptLength::get();
+
+		let token = T::Erc20Source::token().ok_or(BenchmarkError::Weightless)?;
+		let caller: T::AccountId = whitelisted_caller();
+		let migration_vault_account = account::<T::AccountId>("MigrationVault", VAULT_INDEX, SEED);
+		let account_to_credit = account::<T::AccountId>("Bob", 2, SEED);
+		let vault_total = 1_000_000;
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<MigrationOwner<T, I>>::put(caller.clone());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		create_default_minted_asset::<T, I>(migration_vault_account, vault_total);
+
+		let holder = H160::repeat_byte(2);
+		let burn_address = Migration::<T, I>::erc20_burn_address(&account_to_credit);
+		let one_burn = erc20_burn_receipt(token, holder, burn_address, 100).len() as u32;
+		let burn_length = erc20_burns_receipt(token, &[(holder, 100); 2], burn_address).len() as u32 - one_burn;
+		let burns = (1 + r.saturating_sub(one_burn) / burn_length) as usize;
+		let receipt = erc20_burns_receipt(token, &vec![(holder, 100); burns], burn_address);
+		let (receipts_root, mut proof) = build_receipts_trie(&[receipt.clone()]);
+		let receipt: ReceiptOf<T, I> = receipt.try_into().unwrap();
+		proof.extend((1..n).map(|i| vec![i as u8; 512]));
+		let proof: StorageProofOf<T, I> = proof.try_into().unwrap();
+		let header = ethereum_header(1_000, receipts_root);
+		<TrustedEthereumBlocks<T, I>>::insert(H256(sp_io::hashing::keccak_256(&header)), ());
+	}: _(RawOrigin::Signed(caller), header.clone(), 0, receipt, proof, account_to_credit)
+	verify {
+		assert!(<ClaimedReceipts<T, I>>::contains_key(H256(sp_io::hashing::keccak_256(&header)), 0));
+	}
+
+	set_attesters {
+		let a in 1 .. T::MaxAttesters::get();
+
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let attesters = attesters::<T, I>(a);
+	}: _<T::RuntimeOrigin>(origin, attesters, a)
+	verify {
+		assert_eq!(Migration::<T, I>::attester_set_id(), 1);
+	}
+
+	migrate_attested {
+		// Every attester signs, so all their signatures have to be verified.
+		let s in 1 .. T::MaxAttesters::get();
+
+		let migration_vault_account = account::<T::AccountId>("MigrationVault", VAULT_INDEX, SEED);
+		let account_to_credit = account::<T::AccountId>("Bob", 2, SEED);
+		let for_account = source_account::<T, I>(2);
+		let vault_total = 1_000_000;
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<MigrationOwner<T, I>>::put(whitelisted_caller::<T::AccountId>());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		create_default_minted_asset::<T, I>(migration_vault_account, vault_total);
+		let (category, _) = vesting_category::<T, I>(vault_total);
+
+		let attesters = attesters::<T, I>(s);
+		<Attesters<T, I>>::put(attesters.clone());
+		<AttesterThreshold<T, I>>::put(s);
+		let amount: BalanceOf<T, I> = 100u32.into();
+		let source_ref: SourceRef<T, I> = vec![1u8; T::MaxSourceRefLength::get() as usize].try_into().unwrap();
+		let payload = Migration::<T, I>::attestation_payload(&source_ref, &for_account, &account_to_credit, amount, Some(&category));
+		let attestations: AttestationsOf<T, I> = attesters
+			.iter()
+			.enumerate()
+			.map(|(index, attester)| (index as u32, attester.sign(&payload).unwrap()))
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+	}: _(RawOrigin::None, for_account, account_to_credit, amount, source_ref.clone(), Some(category), attestations)
+	verify {
+		assert!(Migration::<T, I>::processed_source_ref(&source_ref).is_some());
+	}
+
+	set_outbound_cap {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let cap: BalanceOf<T, I> = 1_000_000u32.into();
+	}: _<T::RuntimeOrigin>(origin, Some(cap))
+	verify {
+		assert_eq!(Migration::<T, I>::outbound_cap(), Some(cap));
+	}
+
+	migrate_back {
+		let caller: T::AccountId = whitelisted_caller();
+		let migration_vault_account = account::<T::AccountId>("MigrationVault", VAULT_INDEX, SEED);
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		create_default_minted_asset::<T, I>(caller.clone(), 1_000_000);
+		<OutboundCap<T, I>>::put(BalanceOf::<T, I>::from(1_000_000u32));
+		let amount: BalanceOf<T, I> = 100u32.into();
+	}: _(RawOrigin::Signed(caller), source_account::<T, I>(2), amount)
+	verify {
+		assert_eq!(Migration::<T, I>::pending_outbound(), amount);
+	}
+
+	confirm_release {
+		let caller: T::AccountId = whitelisted_caller();
+		<MigrationOwner<T, I>>::put(caller.clone());
+		let amount: BalanceOf<T, I> = 100u32.into();
+		<PendingReleases<T, I>>::insert(0, OutboundRelease { who: caller.clone(), to: source_account::<T, I>(2), amount });
+		<PendingOutbound<T, I>>::put(amount);
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert!(Migration::<T, I>::pending_release(0).is_none());
+	}
+
+	enqueue_migrations {
+		let n in 1 .. T::MaxQueuedMigrations::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		<MigrationOwner<T, I>>::put(caller.clone());
+		let migrations: MigrationBatch<T, I> = (0..n)
+			.map(|i| QueuedMigration {
+				for_account: source_account::<T, I>(i),
+				account_to_credit: account::<T::AccountId>("Bob", i, SEED),
+				amount: 100u32.into(),
+				source_ref: Some(vec![i as u8; T::MaxSourceRefLength::get() as usize].try_into().unwrap()),
+				category: Some(vec![b'c'; T::MaxCategoryNameLength::get() as usize].try_into().unwrap()),
+			})
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+	}: _(RawOrigin::Signed(caller), migrations)
+	verify {
+		assert_eq!(Migration::<T, I>::migration_queue_tail(), n);
+	}
+
+	process_queued_migration {
+		let caller: T::AccountId = whitelisted_caller();
+		let migration_vault_account = account::<T::AccountId>("MigrationVault", VAULT_INDEX, SEED);
+		<MigrationVaultAccount<T, I>>::put(migration_vault_account.clone());
+		<MigrationOwner<T, I>>::put(caller);
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		create_default_minted_asset::<T, I>(migration_vault_account, 1_000_000);
+		let (category, _) = vesting_category::<T, I>(1_000_000);
+
+		<MigrationQueue<T, I>>::insert(0, QueuedMigration {
+			for_account: source_account::<T, I>(2),
+			account_to_credit: account::<T::AccountId>("Bob", 2, SEED),
+			amount: 100u32.into(),
+			source_ref: Some(vec![0u8; T::MaxSourceRefLength::get() as usize].try_into().unwrap()),
+			category: Some(category),
+		});
+		<MigrationQueueTail<T, I>>::put(1);
+		let budget = T::DbWeight::get().reads(3).saturating_add(T::WeightInfo::process_queued_migration()).saturating_add(T::OnMigrated::weight());
+	}: {
+		Migration::<T, I>::process_migration_queue(budget);
+	}
+	verify {
+		assert_eq!(Migration::<T, I>::migration_queue_head(), 1);
+		assert!(Migration::<T, I>::failed_migration(0).is_none());
+	}
+
+	set_category {
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let name: CategoryName<T, I> = vec![b'c'; T::MaxCategoryNameLength::get() as usize].try_into().unwrap();
+		let cap: BalanceOf<T, I> = 1_000_000u32.into();
+		let vesting = Some(CategoryVesting { start: 10u32.into(), duration: 100u32.into() });
+	}: _<T::RuntimeOrigin>(origin, name.clone(), cap, vesting)
+	verify {
+		assert_eq!(Migration::<T, I>::category(&name).map(|category| category.cap), Some(cap));
+	}
+
+	register_destination {
+		let caller: T::AccountId = whitelisted_caller();
+		let destination = account::<T::AccountId>("Bob", 2, SEED);
+		let (for_account, signature) = T::SourceSignature::sign(|for_account| Migration::<T, I>::destination_payload(for_account, &destination))
+			.ok_or(BenchmarkError::Weightless)?;
+	}: _(RawOrigin::Signed(caller), for_account.clone(), destination.clone(), signature)
+	verify {
+		assert_eq!(Migration::<T, I>::bound_destination(&for_account), Some(destination));
+	}
+
+	dispute {
+		let c in 1 .. T::MaxDisputedCredits::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::LocalToken::make_free_balance_be(&caller, T::DisputeBond::get() + T::LocalToken::minimum_balance() * 10u32.into());
+		let evidence_hash = T::Hashing::hash(b"evidence");
+		let (for_account, signature) = T::SourceSignature::sign(|for_account| Migration::<T, I>::dispute_payload(for_account, &evidence_hash))
+			.ok_or(BenchmarkError::Weightless)?;
+		for i in 0 .. c {
+			<Credits<T, I>>::insert(&for_account, account::<T::AccountId>("Credited", i, SEED), BalanceOf::<T, I>::from(100u32));
+		}
+	}: _(RawOrigin::Signed(caller), for_account.clone(), evidence_hash, signature)
+	verify {
+		assert_eq!(Migration::<T, I>::open_dispute(&for_account), Some(0));
+	}
+
+	resolve_dispute {
+		let c in 1 .. T::MaxDisputedCredits::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::LocalToken::make_free_balance_be(&caller, T::DisputeBond::get() + T::LocalToken::minimum_balance() * 10u32.into());
+		<TokenId<T, I>>::put(default_asset_id::<T, I>().into());
+		let (asset_id, _) = create_default_asset::<T, I>();
+		let for_account = source_account::<T, I>(2);
+		let category: CategoryName<T, I> = vec![b'c'; T::MaxCategoryNameLength::get() as usize].try_into().unwrap();
+		let mut credits = Vec::new();
+		for i in 0 .. c {
+			let credited = account::<T::AccountId>("Credited", i, SEED);
+			let amount = BalanceOf::<T, I>::from(100u32);
+			T::Assets::mint_into(asset_id.into(), &credited, 1_000u32.into())?;
+			<Credits<T, I>>::insert(&for_account, &credited, amount);
+			<CategoryAmounts<T, I>>::insert(&credited, &category, amount);
+			<DisputedBalances<T, I>>::insert(&credited, amount);
+			credits.push((credited, amount));
+		}
+		let bond = T::DisputeBond::get();
+		T::LocalToken::reserve(&caller, bond)?;
+		<OpenDisputes<T, I>>::insert(&for_account, 0);
+		<Disputes<T, I>>::insert(0, Dispute {
+			for_account: for_account.clone(),
+			disputer: caller,
+			bond,
+			evidence_hash: T::Hashing::hash(b"evidence"),
+			opened: Zero::zero(),
+			credits: credits.try_into().unwrap(),
+			status: DisputeStatus::Open,
+		});
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let to = account::<T::AccountId>("Bob", 2, SEED);
+	}: _<T::RuntimeOrigin>(origin, 0, Some(to.clone()))
+	verify {
+		assert_eq!(Migration::<T, I>::credited(&for_account, &to), BalanceOf::<T, I>::from(100u32 * c));
+		assert_eq!(Migration::<T, I>::category_amount(&to, &category), BalanceOf::<T, I>::from(100u32 * c));
+	}
+
+	recommit_allocations {
+		let n in 0 .. T::MaxAllocationChunk::get();
+
+		let entries: Vec<(T::SourceAccountId, BalanceOf<T, I>)> = (0..n)
+			.map(|i| (source_account::<T, I>(i), 100u32.into()))
+			.collect();
+		for (source, amount) in entries.iter() {
+			<Allocations<T, I>>::insert(source, amount);
+		}
+		let root = Migration::<T, I>::allocation_commitment(&entries);
+		<AllocationCommitment<T, I>>::put(AllocationCommitmentInfo { root: T::Hash::default(), entries: n });
+		<AllocationUpload<T, I>>::put(AllocationUploadProgress { accumulator: root, uploaded: n });
+		let origin = T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(origin, root, n, n)
+	verify {
+		assert_eq!(Migration::<T, I>::allocation_upload_progress().uploaded, 0);
+		assert_eq!(Migration::<T, I>::allocation_commitment_info().map(|commitment| commitment.root), Some(root));
+	}
+
 	impl_benchmark_test_suite!(Migration, crate::mock::new_default_ext(), crate::mock::TestSuite);
 }
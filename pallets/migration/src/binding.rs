@@ -0,0 +1,104 @@
+//! Destination accounts bound by the holders of source accounts.
+//!
+//! A holder signs the account to credit with the key of their source account, and anyone submits
+//! the signature with `register_destination`. Migrations for a bound source account can only
+//! credit the bound destination, whichever call executes them. The payload includes the number of
+//! earlier bindings of the source account, so a replaced binding cannot be replayed.
+//!
+//! Holders sign disputes of the migrations for their source account with the same key.
+
+use crate::pallet::*;
+use codec::Encode;
+use frame_support::traits::Get;
+use sp_core::{ecdsa, H160};
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+use sp_runtime::{traits::Verify, AccountId32, MultiSignature};
+use sp_std::vec::Vec;
+
+/// Signature by the holder of a source account.
+pub trait SourceSignature<SourceAccountId> {
+	/// Whether this is a signature of `message` by `signer`.
+	fn verify(&self, message: &[u8], signer: &SourceAccountId) -> bool;
+
+	/// A new source account and its signature of the `message` for it, to benchmark binding
+	/// destinations.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn sign(_message: impl FnOnce(&SourceAccountId) -> Vec<u8>) -> Option<(SourceAccountId, Self)>
+	where
+		Self: Sized,
+	{
+		None
+	}
+}
+
+/// Destinations cannot be bound.
+impl<SourceAccountId> SourceSignature<SourceAccountId> for () {
+	fn verify(&self, _message: &[u8], _signer: &SourceAccountId) -> bool {
+		false
+	}
+}
+
+/// Substrate accounts sign with the key they are derived from.
+impl SourceSignature<[u8; 32]> for MultiSignature {
+	fn verify(&self, message: &[u8], signer: &[u8; 32]) -> bool {
+		Verify::verify(self, message, &AccountId32::from(*signer))
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn sign(message: impl FnOnce(&[u8; 32]) -> Vec<u8>) -> Option<([u8; 32], Self)> {
+		let public = sp_io::crypto::sr25519_generate(crate::KEY_TYPE, None);
+		let signature = sp_io::crypto::sr25519_sign(crate::KEY_TYPE, &public, &message(&public.0))?;
+		Some((public.0, signature.into()))
+	}
+}
+
+/// Ethereum accounts sign with `personal_sign` (EIP-191).
+impl SourceSignature<H160> for ecdsa::Signature {
+	fn verify(&self, message: &[u8], signer: &H160) -> bool {
+		ethereum_signer(self, message).as_ref() == Some(signer)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn sign(message: impl FnOnce(&H160) -> Vec<u8>) -> Option<(H160, Self)> {
+		let public = sp_io::crypto::ecdsa_generate(crate::KEY_TYPE, None);
+		let sign = |message: &[u8]| sp_io::crypto::ecdsa_sign_prehashed(crate::KEY_TYPE, &public, &personal_message_hash(message));
+		// The address is only known from a signature.
+		let address = ethereum_signer(&sign(b"")?, b"")?;
+		let message = message(&address);
+		Some((address, sign(&message)?))
+	}
+}
+
+/// Ethereum account that signed `message` with `personal_sign`.
+pub fn ethereum_signer(signature: &ecdsa::Signature, message: &[u8]) -> Option<H160> {
+	let public = secp256k1_ecdsa_recover(&signature.0, &personal_message_hash(message)).ok()?;
+	Some(H160::from_slice(&keccak_256(&public)[12..]))
+}
+
+/// Hash of `message` signed by `personal_sign`.
+pub(crate) fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+	let mut length = Vec::new();
+	let mut remaining = message.len();
+	loop {
+		length.insert(0, b'0' + (remaining % 10) as u8);
+		remaining /= 10;
+		if remaining == 0 {
+			break
+		}
+	}
+	keccak_256(&[&b"\x19Ethereum Signed Message:\n"[..], &length, message].concat())
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Payload the holder of `for_account` signs to bind `destination`.
+	pub fn destination_payload(for_account: &T::SourceAccountId, destination: &T::AccountId) -> Vec<u8> {
+		let nonce = <DestinationBindingNonces<T, I>>::get(for_account);
+		(b"jur/bind", T::PalletId::get(), for_account, destination, nonce).encode()
+	}
+
+	/// Payload the holder of `for_account` signs to dispute its migrations with `evidence_hash`.
+	pub fn dispute_payload(for_account: &T::SourceAccountId, evidence_hash: &T::Hash) -> Vec<u8> {
+		let nonce = <DisputeNonces<T, I>>::get(for_account);
+		(b"jur/disp", T::PalletId::get(), for_account, evidence_hash, nonce).encode()
+	}
+}
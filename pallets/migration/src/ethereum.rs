@@ -0,0 +1,288 @@
+//! ERC-20 burns on Ethereum proven by transaction receipts.
+//!
+//! Relayers add the hashes of Ethereum blocks they consider final. A claimant submits the RLP
+//! encoded header of such a block, the receipt of their burn transaction and the Merkle-Patricia
+//! proof of the receipt against the receipts root of the header. Tokens are burned by a `Transfer`
+//! to the burn address of the account to credit, which nobody holds the key of.
+
+use crate::pallet::*;
+use codec::Encode;
+use frame_support::traits::Get;
+use rlp::Rlp;
+use sp_core::{H160, H256};
+use sp_io::hashing::keccak_256;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// ERC-20 token on Ethereum that is migrated by burning it.
+pub trait Erc20Source<SourceAccountId> {
+	/// Address of the token contract, `None` if no ERC-20 token is migrated.
+	fn token() -> Option<H160>;
+
+	/// Source account of the Ethereum `holder`.
+	fn holder(address: H160) -> Option<SourceAccountId>;
+}
+
+/// No ERC-20 token is migrated.
+impl<SourceAccountId> Erc20Source<SourceAccountId> for () {
+	fn token() -> Option<H160> {
+		None
+	}
+
+	fn holder(_address: H160) -> Option<SourceAccountId> {
+		None
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Ethereum address that ERC-20 tokens are burned to for `account_to_credit`.
+	pub fn erc20_burn_address(account_to_credit: &T::AccountId) -> H160 {
+		let hash = (b"jur/burn", T::PalletId::get(), account_to_credit).using_encoded(keccak_256);
+		H160::from_slice(&hash[12..])
+	}
+
+	/// Receipts root of the RLP encoded Ethereum block `header`.
+	pub(crate) fn receipts_root(header: &[u8]) -> Option<H256> {
+		// [parent hash, ommers hash, beneficiary, state root, transactions root, receipts root, ..]
+		let root = Rlp::new(header).at(5).ok()?;
+		let root = root.data().ok()?;
+		(root.len() == 32).then(|| H256::from_slice(root))
+	}
+
+	/// Value at `key` of the Merkle-Patricia trie with `root`, proven by the RLP encoded nodes in
+	/// `proof`.
+	pub(crate) fn read_ethereum_trie(root: &H256, key: &[u8], proof: &[Vec<u8>]) -> Option<Vec<u8>> {
+		let nodes = proof.iter().map(|node| (keccak_256(node), node)).collect::<BTreeMap<_, _>>();
+		let nibbles = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+		let mut path = &nibbles[..];
+		let mut node = nodes.get(&root.0)?.to_vec();
+
+		// Every node but the last consumes at least one nibble of the key.
+		for _ in 0..=nibbles.len() {
+			let rlp = Rlp::new(&node);
+			let child = match rlp.item_count().ok()? {
+				// Branch: a child for each nibble and the value.
+				17 => match path.split_first() {
+					None => return Some(rlp.at(16).ok()?.data().ok()?.to_vec()).filter(|value| !value.is_empty()),
+					Some((nibble, rest)) => {
+						path = rest;
+						rlp.at(*nibble as usize).ok()?
+					},
+				},
+				// Leaf or extension with its hex-prefix encoded path.
+				2 => {
+					let (node_path, is_leaf) = decode_hex_prefix(rlp.at(0).ok()?.data().ok()?)?;
+					path = path.strip_prefix(&node_path[..])?;
+					if is_leaf {
+						if !path.is_empty() {
+							return None
+						}
+						return Some(rlp.at(1).ok()?.data().ok()?.to_vec())
+					}
+					rlp.at(1).ok()?
+				},
+				_ => return None,
+			};
+			// Children shorter than a hash are embedded in their parent.
+			node = if child.is_list() {
+				child.as_raw().to_vec()
+			} else {
+				let hash = child.data().ok()?;
+				if hash.len() != 32 {
+					return None
+				}
+				nodes.get(hash)?.to_vec()
+			};
+		}
+		None
+	}
+
+	/// Holder and total amount of the burns of `token` to `burn_address` in the RLP encoded
+	/// transaction `receipt`, if the transaction succeeded. `None` if the burns are from several
+	/// holders.
+	pub(crate) fn erc20_burn(receipt: &[u8], token: &H160, burn_address: &H160) -> Option<(H160, u128)> {
+		// Receipts of typed transactions (EIP-2718) are prefixed with the type.
+		let receipt = match receipt.first()? {
+			0x00..=0x7f => &receipt[1..],
+			_ => receipt,
+		};
+		// [status, cumulative gas used, logs bloom, logs]
+		let receipt = Rlp::new(receipt);
+		if receipt.at(0).ok()?.data().ok()? != [1u8] {
+			return None
+		}
+
+		let transfer = keccak_256(b"Transfer(address,address,uint256)");
+		let mut burned: Option<(H160, u128)> = None;
+		for log in receipt.at(3).ok()?.iter() {
+			// [address, topics, data]
+			let topics = log.at(1).ok()?;
+			let topic = |index| topics.at(index).ok().and_then(|topic| topic.data().ok().filter(|topic| topic.len() == 32));
+			if log.at(0).ok()?.data().ok()? != token.as_bytes() ||
+				topics.item_count().ok()? != 3 ||
+				topic(0)? != transfer ||
+				&topic(2)?[12..] != burn_address.as_bytes()
+			{
+				continue
+			}
+
+			let value = log.at(2).ok()?;
+			let value = value.data().ok()?;
+			// The amount is a `uint256`, larger amounts than `u128` cannot be migrated.
+			if value.len() != 32 || value[..16].iter().any(|byte| *byte != 0) {
+				return None
+			}
+			let mut amount = [0u8; 16];
+			amount.copy_from_slice(&value[16..]);
+			let holder = H160::from_slice(&topic(1)?[12..]);
+			let total = match burned {
+				Some((first, total)) if first == holder => total.checked_add(u128::from_be_bytes(amount))?,
+				Some(_) => return None,
+				None => u128::from_be_bytes(amount),
+			};
+			burned = Some((holder, total));
+		}
+		burned
+	}
+}
+
+/// Nibbles of a hex-prefix encoded path, and whether it is the path of a leaf.
+fn decode_hex_prefix(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
+	let (first, rest) = encoded.split_first()?;
+	let flag = first >> 4;
+	if flag > 3 {
+		return None
+	}
+	let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+	if flag & 1 == 1 {
+		nibbles.push(first & 0x0f);
+	}
+	nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+	Some((nibbles, flag & 2 == 2))
+}
+
+/// RLP encoded receipt of a successful transaction burning `amount` of `token` held by `from`.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn erc20_burn_receipt(token: H160, from: H160, burn_address: H160, amount: u128) -> Vec<u8> {
+	erc20_burns_receipt(token, &[(from, amount)], burn_address)
+}
+
+/// RLP encoded receipt of a successful transaction with a burn of `token` for each of `burns`.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn erc20_burns_receipt(token: H160, burns: &[(H160, u128)], burn_address: H160) -> Vec<u8> {
+	let topic = |address: H160| H256::from(address).as_bytes().to_vec();
+
+	let mut receipt = rlp::RlpStream::new_list(4);
+	receipt.append(&1u8).append(&21_000u64).append(&[0u8; 256].to_vec());
+	receipt.begin_list(burns.len());
+	for (from, amount) in burns {
+		let mut value = [0u8; 32];
+		value[16..].copy_from_slice(&amount.to_be_bytes());
+		receipt.begin_list(3).append(&token.as_bytes().to_vec());
+		receipt
+			.begin_list(3)
+			.append(&keccak_256(b"Transfer(address,address,uint256)").to_vec())
+			.append(&topic(*from))
+			.append(&topic(burn_address));
+		receipt.append(&value.to_vec());
+	}
+	receipt.out().to_vec()
+}
+
+/// Receipts root of a block with `receipts`, and all nodes of the receipts trie as a proof.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn build_receipts_trie(receipts: &[Vec<u8>]) -> (H256, Vec<Vec<u8>>) {
+	fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+		let flag = (if is_leaf { 2 } else { 0 }) + (nibbles.len() % 2) as u8;
+		let mut encoded = Vec::new();
+		let rest = if nibbles.len() % 2 == 1 {
+			encoded.push(flag << 4 | nibbles[0]);
+			&nibbles[1..]
+		} else {
+			encoded.push(flag << 4);
+			nibbles
+		};
+		encoded.extend(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+		encoded
+	}
+
+	fn append_child(stream: &mut rlp::RlpStream, node: Vec<u8>, proof: &mut Vec<Vec<u8>>) {
+		if node.len() < 32 {
+			stream.append_raw(&node, 1);
+		} else {
+			stream.append(&keccak_256(&node).to_vec());
+			proof.push(node);
+		}
+	}
+
+	fn build(entries: &[(Vec<u8>, Vec<u8>)], depth: usize, proof: &mut Vec<Vec<u8>>) -> Vec<u8> {
+		if let [(key, value)] = entries {
+			let mut leaf = rlp::RlpStream::new_list(2);
+			leaf.append(&hex_prefix(&key[depth..], true)).append(value);
+			return leaf.out().to_vec()
+		}
+
+		let (first, _) = &entries[0];
+		let shared = (depth..first.len())
+			.take_while(|at| entries.iter().all(|(key, _)| key.get(*at) == Some(&first[*at])))
+			.count();
+		if shared > 0 {
+			let mut extension = rlp::RlpStream::new_list(2);
+			extension.append(&hex_prefix(&first[depth..depth + shared], false));
+			append_child(&mut extension, build(entries, depth + shared, proof), proof);
+			return extension.out().to_vec()
+		}
+
+		let mut branch = rlp::RlpStream::new_list(17);
+		for nibble in 0..16u8 {
+			let children = entries.iter().filter(|(key, _)| key.get(depth) == Some(&nibble)).cloned().collect::<Vec<_>>();
+			if children.is_empty() {
+				branch.append_empty_data();
+			} else {
+				append_child(&mut branch, build(&children, depth + 1, proof), proof);
+			}
+		}
+		match entries.iter().find(|(key, _)| key.len() == depth) {
+			Some((_, value)) => branch.append(value),
+			None => branch.append_empty_data(),
+		};
+		branch.out().to_vec()
+	}
+
+	// Receipts are keyed by the RLP encoded transaction index.
+	let entries = receipts
+		.iter()
+		.enumerate()
+		.map(|(index, receipt)| {
+			let key = rlp::encode(&(index as u64));
+			(key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect(), receipt.clone())
+		})
+		.collect::<Vec<(Vec<u8>, Vec<u8>)>>();
+	let mut proof = Vec::new();
+	let root = build(&entries, 0, &mut proof);
+	let hash = H256(keccak_256(&root));
+	proof.push(root);
+	(hash, proof)
+}
+
+/// RLP encoded header of Ethereum block `number` with `receipts_root`.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn ethereum_header(number: u64, receipts_root: H256) -> Vec<u8> {
+	let mut header = rlp::RlpStream::new_list(15);
+	header
+		.append(&H256::repeat_byte(number as u8).as_bytes().to_vec())
+		.append(&[0u8; 32].to_vec())
+		.append(&[0u8; 20].to_vec())
+		.append(&[0u8; 32].to_vec())
+		.append(&[0u8; 32].to_vec())
+		.append(&receipts_root.as_bytes().to_vec())
+		.append(&[0u8; 256].to_vec())
+		.append(&0u64)
+		.append(&number)
+		.append(&30_000_000u64)
+		.append(&21_000u64)
+		.append(&1_700_000_000u64)
+		.append(&Vec::<u8>::new())
+		.append(&[0u8; 32].to_vec())
+		.append(&[0u8; 8].to_vec());
+	header.out().to_vec()
+}
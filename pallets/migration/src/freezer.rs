@@ -0,0 +1,95 @@
+//! Freezer for runtimes with several instances of the pallet, and the weight of freezer queries.
+
+use crate::pallet::*;
+use frame_support::{traits::Get, weights::Weight};
+use pallet_assets::FrozenBalance;
+use sp_runtime::traits::Saturating;
+use sp_std::marker::PhantomData;
+
+/// Use as the `pallet_assets` `Freezer` when the assets of several instances live in the same
+/// assets pallet, e.g. `CombinedFreezer<Migration, CombinedFreezer<MigrationB, MigrationC>>`.
+///
+/// `pallet_assets` only takes one freezer, so without it only one instance can enforce its
+/// transfer lock, category vesting and disputes. Each instance only freezes its own asset, and
+/// the amounts frozen by both are added up.
+pub struct CombinedFreezer<A, B>(PhantomData<(A, B)>);
+
+impl<AssetId, AccountId, Balance, A, B> FrozenBalance<AssetId, AccountId, Balance> for CombinedFreezer<A, B>
+where
+	AssetId: Clone,
+	Balance: Saturating,
+	A: FrozenBalance<AssetId, AccountId, Balance>,
+	B: FrozenBalance<AssetId, AccountId, Balance>,
+{
+	fn frozen_balance(asset: AssetId, who: &AccountId) -> Option<Balance> {
+		match (A::frozen_balance(asset.clone(), who), B::frozen_balance(asset, who)) {
+			(Some(a), Some(b)) => Some(a.saturating_add(b)),
+			(a, b) => a.or(b),
+		}
+	}
+
+	fn died(asset: AssetId, who: &AccountId) {
+		A::died(asset.clone(), who);
+		B::died(asset, who);
+	}
+}
+
+/// Worst-case weight of a `FrozenBalance::frozen_balance` query, which the weights of
+/// `pallet_assets` don't include.
+pub trait FreezerWeight {
+	fn frozen_balance() -> Weight;
+}
+
+impl<T: Config<I>, I: 'static> FreezerWeight for Pallet<T, I> {
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration CategoryVaults (r:1 w:0)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Migration CategoryAmounts (r:MaxCategories + 1 w:0)
+	// Storage: Migration Categories (r:MaxCategories w:0)
+	// Storage: Migration DisputedBalances (r:1 w:0)
+	fn frozen_balance() -> Weight {
+		T::DbWeight::get().reads(6 + 2 * T::MaxCategories::get() as u64)
+	}
+}
+
+impl<A: FreezerWeight, B: FreezerWeight> FreezerWeight for CombinedFreezer<A, B> {
+	fn frozen_balance() -> Weight {
+		A::frozen_balance().saturating_add(B::frozen_balance())
+	}
+}
+
+/// `pallet_assets` weights `W` plus the query of the freezer `F` for every call that debits an
+/// account, e.g. `FreezerWeightInfo<pallet_assets::weights::SubstrateWeight<Runtime>, Migration>`.
+pub struct FreezerWeightInfo<W, F>(PhantomData<(W, F)>);
+
+impl<W: pallet_assets::WeightInfo, F: FreezerWeight> pallet_assets::WeightInfo for FreezerWeightInfo<W, F> {
+	fn create() -> Weight { W::create() }
+	fn force_create() -> Weight { W::force_create() }
+	fn start_destroy() -> Weight { W::start_destroy() }
+	fn destroy_accounts(c: u32) -> Weight { W::destroy_accounts(c) }
+	fn destroy_approvals(a: u32) -> Weight { W::destroy_approvals(a) }
+	fn finish_destroy() -> Weight { W::finish_destroy() }
+	fn mint() -> Weight { W::mint() }
+	fn burn() -> Weight { W::burn().saturating_add(F::frozen_balance()) }
+	fn transfer() -> Weight { W::transfer().saturating_add(F::frozen_balance()) }
+	fn transfer_keep_alive() -> Weight { W::transfer_keep_alive().saturating_add(F::frozen_balance()) }
+	fn force_transfer() -> Weight { W::force_transfer().saturating_add(F::frozen_balance()) }
+	fn freeze() -> Weight { W::freeze() }
+	fn thaw() -> Weight { W::thaw() }
+	fn freeze_asset() -> Weight { W::freeze_asset() }
+	fn thaw_asset() -> Weight { W::thaw_asset() }
+	fn transfer_ownership() -> Weight { W::transfer_ownership() }
+	fn set_team() -> Weight { W::set_team() }
+	fn set_metadata(n: u32, s: u32) -> Weight { W::set_metadata(n, s) }
+	fn clear_metadata() -> Weight { W::clear_metadata() }
+	fn force_set_metadata(n: u32, s: u32) -> Weight { W::force_set_metadata(n, s) }
+	fn force_clear_metadata() -> Weight { W::force_clear_metadata() }
+	fn force_asset_status() -> Weight { W::force_asset_status() }
+	fn approve_transfer() -> Weight { W::approve_transfer() }
+	fn transfer_approved() -> Weight { W::transfer_approved().saturating_add(F::frozen_balance()) }
+	fn cancel_approval() -> Weight { W::cancel_approval() }
+	fn force_cancel_approval() -> Weight { W::force_cancel_approval() }
+	fn touch() -> Weight { W::touch() }
+	fn refund() -> Weight { W::refund() }
+}
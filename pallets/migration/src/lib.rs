@@ -2,6 +2,16 @@
 
 pub use pallet::*;
 pub use weights::WeightInfo;
+pub use offchain::PendingMigration;
+pub use signed_extension::CheckMigration;
+pub use sponsorship::SponsoredCurrencyAdapter;
+pub use storage_proof::SourceBalanceEntry;
+pub use light_client::{GrandpaJustification, SourceBurn, SourceHeader};
+pub use ethereum::Erc20Source;
+pub use binding::SourceSignature;
+pub use freezer::{CombinedFreezer, FreezerWeight, FreezerWeightInfo};
+
+use sp_core::crypto::KeyTypeId;
 
 #[cfg(test)]
 mod mock;
@@ -17,46 +27,141 @@ mod benchmarking;
 
 pub mod weights;
 
+mod offchain;
+pub mod signed_extension;
+pub mod sponsorship;
+mod storage_proof;
+mod light_client;
+mod ethereum;
+mod attestation;
+mod binding;
+mod freezer;
+
+/// Key type of the migration owner key used by the off-chain worker to sign `migrate` calls.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"migr");
+
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct MigrationAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for MigrationAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Handler of successful migrations, e.g. to register identities, start vesting or grant gas
+/// to migrated accounts.
+pub trait OnMigrated<SourceAccountId, AccountId, Balance, TokenId> {
+	/// Called once `amount` of the asset `token_id` was transferred to `who` for `for_account`.
+	fn on_migrated(for_account: &SourceAccountId, who: &AccountId, amount: Balance, token_id: TokenId);
+
+	/// Maximum weight of `on_migrated`, charged to every call that migrates.
+	fn weight() -> frame_support::weights::Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<SourceAccountId, AccountId, Balance: Copy, TokenId: Copy> OnMigrated<SourceAccountId, AccountId, Balance, TokenId> for Tuple {
+	fn on_migrated(for_account: &SourceAccountId, who: &AccountId, amount: Balance, token_id: TokenId) {
+		for_tuples!( #( Tuple::on_migrated(for_account, who, amount, token_id); )* );
+	}
+
+	fn weight() -> frame_support::weights::Weight {
+		let mut weight = frame_support::weights::Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+		weight
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	#[allow(unused)]
 	use frame_support::{ debug };
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
-	use frame_support::traits::{ Currency };
-	use frame_support::sp_runtime::traits::{ IdentifyAccount, Convert};
-	use frame_support::sp_runtime::FixedPointOperand;
+	use frame_system::offchain::{AppCrypto, CreateSignedTransaction};
+	use frame_support::dispatch::{DispatchClass, Pays};
+	use frame_support::traits::{ Currency, ReservableCurrency, OnUnbalanced };
+	use frame_support::sp_runtime::traits::{ IdentifyAccount, Convert, CheckedAdd, AccountIdConversion, Hash as HashT, Header as HeaderT, Saturating, Zero};
+	use frame_support::PalletId;
+	use frame_support::sp_runtime::ArithmeticError;
+	use frame_support::sp_runtime::transaction_validity::{TransactionLongevity, TransactionPriority};
+	use frame_support::sp_runtime::{FixedPointNumber, FixedPointOperand, FixedU128, PerThing, Perbill, SaturatedConversion};
+	use frame_support::traits::tokens::BalanceConversion;
+	use sp_core::H256;
+	use crate::{OnMigrated, SourceBalanceEntry, SourceBurn, SourceHeader, GrandpaJustification, Erc20Source, SourceSignature};
+	use sp_finality_grandpa::AuthorityId as SourceAuthorityId;
+	use sp_runtime::RuntimeAppPublic;
     use frame_support::traits::tokens::{Balance};
 	use frame_support::traits::fungibles::{Inspect, Transfer, Create, Mutate};
+	use frame_support::storage::{with_transaction, TransactionOutcome};
+	use pallet_assets::FrozenBalance;
+	use sp_std::vec::Vec;
 	use crate::weights::*;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
-	pub struct Pallet<T>(_);
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
 	#[cfg(feature = "runtime-benchmarks")]
-	pub trait BenchmarkHelper<AssetIdParameter> {
+	pub trait BenchmarkHelper<AssetIdParameter, SourceAccountId> {
 		fn create_asset_id_parameter(id: u32) -> AssetIdParameter;
+		fn create_source_account(index: u32) -> SourceAccountId;
 	}
 	#[cfg(feature = "runtime-benchmarks")]
-	impl<AssetIdParameter: From<u32>> BenchmarkHelper<AssetIdParameter> for () {
+	impl<AssetIdParameter: From<u32>, SourceAccountId: From<[u8; 32]>> BenchmarkHelper<AssetIdParameter, SourceAccountId> for () {
 		fn create_asset_id_parameter(id: u32) -> AssetIdParameter {
 			id.into()
 		}
+		fn create_source_account(index: u32) -> SourceAccountId {
+			sp_io::hashing::blake2_256(&index.encode()).into()
+		}
 	}
 
-	type BalanceOf<T> = <<T as Config>::LocalToken as Currency<<T as frame_system::Config>::AccountId>>::Balance;
-	type AssetBalanceOf<T> = <T as Config>::AssetBalance;
+	pub(crate) type BalanceOf<T, I = ()> = <<T as Config<I>>::LocalToken as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub(crate) type NegativeImbalanceOf<T, I = ()> = <<T as Config<I>>::LocalToken as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+	type AssetBalanceOf<T, I = ()> = <T as Config<I>>::AssetBalance;
+	/// Reference to the source-chain transfer a migration originates from.
+	pub type SourceRef<T, I = ()> = BoundedVec<u8, <T as Config<I>>::MaxSourceRefLength>;
+	pub type AttestersOf<T, I = ()> = BoundedVec<<T as Config<I>>::AttesterId, <T as Config<I>>::MaxAttesters>;
+	/// Signatures of a migration by attesters, with the index of each attester in the current set.
+	pub type AttestationsOf<T, I = ()> = BoundedVec<(u32, <<T as Config<I>>::AttesterId as RuntimeAppPublic>::Signature), <T as Config<I>>::MaxAttesters>;
+	/// A chunk of the allocation table: source account and the amount it may receive.
+	pub type AllocationChunk<T, I = ()> = BoundedVec<(<T as Config<I>>::SourceAccountId, BalanceOf<T, I>), <T as Config<I>>::MaxAllocationChunk>;
+	/// Indices of the pallets whose calls a sponsor pays fees for.
+	pub type SponsoredPallets<T, I = ()> = BoundedVec<u8, <T as Config<I>>::MaxSponsoredPallets>;
+	/// Trie nodes proving a storage entry of the source chain.
+	pub type StorageProofOf<T, I = ()> = BoundedVec<Vec<u8>, <T as Config<I>>::MaxProofNodes>;
+	/// RLP encoded Ethereum transaction receipt.
+	pub type ReceiptOf<T, I = ()> = BoundedVec<u8, <T as Config<I>>::MaxReceiptLength>;
+	pub type QueuedMigrationOf<T, I = ()> = QueuedMigration<<T as Config<I>>::SourceAccountId, <T as frame_system::Config>::AccountId, BalanceOf<T, I>, SourceRef<T, I>, CategoryName<T, I>>;
+	/// Migrations queued at once with `enqueue_migrations`.
+	pub type MigrationBatch<T, I = ()> = BoundedVec<QueuedMigrationOf<T, I>, <T as Config<I>>::MaxQueuedMigrations>;
+	/// Name of an allocation category, e.g. `public` or `team`.
+	pub type CategoryName<T, I = ()> = BoundedVec<u8, <T as Config<I>>::MaxCategoryNameLength>;
+	pub type AllocationCategoryOf<T, I = ()> = AllocationCategory<<T as frame_system::Config>::AccountId, BalanceOf<T, I>, <T as frame_system::Config>::BlockNumber>;
+	/// Accounts credited for a disputed source account and the amounts frozen in them.
+	pub type DisputedCreditsOf<T, I = ()> = BoundedVec<(<T as frame_system::Config>::AccountId, BalanceOf<T, I>), <T as Config<I>>::MaxDisputedCredits>;
+	pub type DisputeOf<T, I = ()> = Dispute<<T as Config<I>>::SourceAccountId, <T as frame_system::Config>::AccountId, BalanceOf<T, I>, <T as frame_system::Config>::BlockNumber, <T as frame_system::Config>::Hash, DisputedCreditsOf<T, I>>;
+	/// GRANDPA authorities of the source chain with their weights.
+	pub type SourceAuthoritiesOf<T, I = ()> = BoundedVec<(SourceAuthorityId, u64), <T as Config<I>>::MaxSourceAuthorities>;
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
-		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-		type LocalToken: Currency<Self::AccountId>;
+	pub trait Config<I: 'static = ()>: frame_system::Config + CreateSignedTransaction<Call<Self, I>> {
+		type RuntimeEvent: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		type LocalToken: ReservableCurrency<Self::AccountId>;
 		type TokenId: Member + Parameter + Copy + MaybeSerializeDeserialize + MaxEncodedLen;
 		
 		// Two-way conversion between asset and currency balances
-		type AssetToCurrencyBalance: Convert<Self::AssetBalance, BalanceOf<Self>>;
-		type CurrencyToAssetBalance: Convert<BalanceOf<Self>, Self::AssetBalance>;
+		type AssetToCurrencyBalance: Convert<Self::AssetBalance, BalanceOf<Self, I>>;
+		type CurrencyToAssetBalance: Convert<BalanceOf<Self, I>, Self::AssetBalance>;
 		
 		type AssetBalance: Balance
 			+ FixedPointOperand
@@ -68,9 +173,117 @@ pub mod pallet {
 		type MigrationOwner: IdentifyAccount;
 		type WeightInfo: crate::weights::WeightInfo;
 
+		/// Identifier of an account on the source chain, e.g. `[u8; 32]`, `H160` or a type tagging
+		/// the chain it belongs to.
+		type SourceAccountId: Parameter + Member + MaxEncodedLen;
+
+		/// Maximum length of a source-chain reference (tx hash, burn event id) passed to `migrate`.
+		#[pallet::constant]
+		type MaxSourceRefLength: Get<u32>;
+
+		/// Key used by the off-chain worker to sign `migrate` on behalf of the migration owner.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// Blocks the off-chain worker waits for a submitted migration to be included before
+		/// submitting it again.
+		#[pallet::constant]
+		type OffchainRetryDelay: Get<Self::BlockNumber>;
+
+		/// Submissions of a queued migration before the off-chain worker gives up on it.
+		#[pallet::constant]
+		type MaxOffchainRetries: Get<u32>;
+
+		/// Maximum number of migrations the off-chain worker submits per block.
+		#[pallet::constant]
+		type MaxOffchainSubmissions: Get<u32>;
+
+		/// Maximum number of entries in a single `upload_allocations` call.
+		#[pallet::constant]
+		type MaxAllocationChunk: Get<u32>;
+
+		/// Priority of valid `migrate` transactions in the pool.
+		#[pallet::constant]
+		type MigrationTxPriority: Get<TransactionPriority>;
+
+		/// Number of blocks a valid `migrate` transaction stays valid in the pool.
+		#[pallet::constant]
+		type MigrationTxLongevity: Get<TransactionLongevity>;
+
+		/// Origin allowed to set the transfer lock on migrated tokens, the fee rate, source state
+		/// roots, the light client checkpoint, the attesters and to register sponsors and relayers.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Id the pallet account is derived from. The pallet account owns the migrated asset, so
+		/// nobody can mint it or change its roles.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Maximum number of pallets a sponsorship can cover.
+		#[pallet::constant]
+		type MaxSponsoredPallets: Get<u32>;
+
+		/// Storage entry of the source chain that balances are proven from.
+		#[pallet::constant]
+		type SourceBalanceEntry: Get<SourceBalanceEntry>;
+
+		/// Maximum number of trie nodes in a source-chain storage proof.
+		#[pallet::constant]
+		type MaxProofNodes: Get<u32>;
+
+		/// Maximum number of GRANDPA authorities of the source chain.
+		#[pallet::constant]
+		type MaxSourceAuthorities: Get<u32>;
+
+		/// Burns on the source chain that can be proven from its events.
+		type SourceBurns: SourceBurn<Self::SourceAccountId, Self::AccountId>;
+
+		/// ERC-20 token on Ethereum whose burns can be proven with transaction receipts.
+		type Erc20Source: Erc20Source<Self::SourceAccountId>;
+
+		/// Maximum length of an RLP encoded Ethereum transaction receipt.
+		#[pallet::constant]
+		type MaxReceiptLength: Get<u32>;
+
+		/// Called after every successful migration.
+		type OnMigrated: OnMigrated<Self::SourceAccountId, Self::AccountId, BalanceOf<Self, I>, Self::TokenId>;
+
+		/// Key of the attesters that sign migrations observed on the source chain.
+		type AttesterId: Member + Parameter + RuntimeAppPublic + Ord + MaxEncodedLen;
+
+		/// Maximum number of attesters.
+		#[pallet::constant]
+		type MaxAttesters: Get<u32>;
+
+		/// Maximum number of migrations waiting in the queue drained in `on_idle`.
+		#[pallet::constant]
+		type MaxQueuedMigrations: Get<u32>;
+
+		/// Maximum length of the name of an allocation category.
+		#[pallet::constant]
+		type MaxCategoryNameLength: Get<u32>;
+
+		/// Maximum number of allocation categories.
+		#[pallet::constant]
+		type MaxCategories: Get<u32>;
+
+		/// Signature of a source-chain holder binding the account their tokens are migrated to.
+		type SourceSignature: Parameter + SourceSignature<Self::SourceAccountId>;
+
+		/// Bond reserved from the account opening a dispute. It is returned if the disputed credits
+		/// are redirected and slashed if they are released.
+		#[pallet::constant]
+		type DisputeBond: Get<BalanceOf<Self, I>>;
+
+		/// Maximum number of accounts credited for a source account that can be disputed.
+		#[pallet::constant]
+		type MaxDisputedCredits: Get<u32>;
+
+		/// Receives the slashed bonds of rejected disputes, e.g. the treasury.
+		type DisputeSlash: OnUnbalanced<NegativeImbalanceOf<Self, I>>;
+
 		// Helper trait for benchmarks.
 		#[cfg(feature = "runtime-benchmarks")]
-		type BenchmarkHelper: BenchmarkHelper<Self::AssetIdParameter>;
+		type BenchmarkHelper: BenchmarkHelper<Self::AssetIdParameter, Self::SourceAccountId>;
 
 		#[cfg(feature = "runtime-benchmarks")]
 		type AssetIdParameter: Parameter
@@ -80,122 +293,1408 @@ pub mod pallet {
 		+ MaxEncodedLen;
 	}
 
-	pub trait ConfigHelper: Config {
-        fn currency_to_asset(curr_balance: BalanceOf<Self>) -> AssetBalanceOf<Self>;
-        fn asset_to_currency(asset_balance: AssetBalanceOf<Self>) -> BalanceOf<Self>;
+	pub trait ConfigHelper<I: 'static = ()>: Config<I> {
+        fn currency_to_asset(curr_balance: BalanceOf<Self, I>) -> AssetBalanceOf<Self, I>;
+        fn asset_to_currency(asset_balance: AssetBalanceOf<Self, I>) -> BalanceOf<Self, I>;
     }
 
-    impl<T: Config> ConfigHelper for T {
+    impl<T: Config<I>, I: 'static> ConfigHelper<I> for T {
         #[inline(always)]
-        fn currency_to_asset(curr_balance: BalanceOf<Self>) -> AssetBalanceOf<Self> {
-            Self::CurrencyToAssetBalance::convert(curr_balance)
+        fn currency_to_asset(curr_balance: BalanceOf<Self, I>) -> AssetBalanceOf<Self, I> {
+            <Self as Config<I>>::CurrencyToAssetBalance::convert(curr_balance)
         }
 
         #[inline(always)]
-        fn asset_to_currency(asset_balance: AssetBalanceOf<Self>) -> BalanceOf<Self> {
-            Self::AssetToCurrencyBalance::convert(asset_balance)
+        fn asset_to_currency(asset_balance: AssetBalanceOf<Self, I>) -> BalanceOf<Self, I> {
+            <Self as Config<I>>::AssetToCurrencyBalance::convert(asset_balance)
         }
     }
 
 	#[pallet::storage]
 	#[pallet::getter(fn get_vault)]
-	pub type MigrationVaultAccount<T: Config> = StorageValue<_, T::AccountId>;
+	pub type MigrationVaultAccount<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn get_owner)]
-	pub type MigrationOwner<T: Config> = StorageValue<_, T::AccountId>;
+	pub type MigrationOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn get_token_id)]
-	pub type TokenId<T: Config> = StorageValue<_, T::TokenId>;
+	pub type TokenId<T: Config<I>, I: 'static = ()> = StorageValue<_, T::TokenId>;
+
+	/// Source-chain references already used by a migration, with the block they were processed in.
+	#[pallet::storage]
+	#[pallet::getter(fn processed_source_ref)]
+	pub type ProcessedSourceRefs<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, SourceRef<T, I>, T::BlockNumber>;
+
+	/// Commitment to the full allocation table, set by `commit_allocations`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct AllocationCommitmentInfo<Hash> {
+		/// Hash chain over all entries, see [`Pallet::allocation_commitment`].
+		pub root: Hash,
+		/// Number of entries in the table.
+		pub entries: u32,
+	}
+
+	/// Progress of the allocation table upload.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+	pub struct AllocationUploadProgress<Hash> {
+		/// Hash chain over the entries uploaded so far.
+		pub accumulator: Hash,
+		/// Number of entries uploaded so far.
+		pub uploaded: u32,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn allocation_commitment_info)]
+	pub type AllocationCommitment<T: Config<I>, I: 'static = ()> = StorageValue<_, AllocationCommitmentInfo<T::Hash>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn allocation_upload_progress)]
+	pub type AllocationUpload<T: Config<I>, I: 'static = ()> = StorageValue<_, AllocationUploadProgress<T::Hash>, ValueQuery>;
+
+	/// Whether the uploaded allocation table matched its commitment and is enforced by `migrate`.
+	#[pallet::storage]
+	#[pallet::getter(fn allocations_sealed)]
+	pub type AllocationsSealed<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	/// Amount each source account may receive in total.
+	#[pallet::storage]
+	#[pallet::getter(fn allocation)]
+	pub type Allocations<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, BalanceOf<T, I>>;
+
+	/// Amount migrated so far for each source account, tracked once allocations are committed.
+	#[pallet::storage]
+	#[pallet::getter(fn migrated_amount)]
+	pub type MigratedAmounts<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, BalanceOf<T, I>, ValueQuery>;
+
+	/// Block from which migrated tokens can be transferred. Until then the migrated asset is
+	/// frozen in every account but the vault.
+	#[pallet::storage]
+	#[pallet::getter(fn transfer_lock_until)]
+	pub type TransferLockUntil<T: Config<I>, I: 'static = ()> = StorageValue<_, T::BlockNumber>;
+
+	/// Units of the migrated asset charged per unit of the local currency when fees are paid in
+	/// the migrated asset. Fees can't be paid in the asset while unset.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_rate)]
+	pub type FeeRate<T: Config<I>, I: 'static = ()> = StorageValue<_, FixedU128>;
+
+	/// Accounts credited by a migration. Only those can be sponsored.
+	#[pallet::storage]
+	pub type MigrationRecipients<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// Accounts allowed to sponsor the fees of migration recipients.
+	#[pallet::storage]
+	pub type Sponsors<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// Sponsorship of each sponsored account.
+	#[pallet::storage]
+	#[pallet::getter(fn sponsorship)]
+	pub type Sponsorships<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, Sponsorship<T::AccountId, BalanceOf<T, I>, SponsoredPallets<T, I>>>;
+
+	/// Trusted state roots of the source chain by source block number. Balances proven against
+	/// them can be claimed with `claim_with_storage_proof`.
+	#[pallet::storage]
+	#[pallet::getter(fn source_state_root)]
+	pub type SourceStateRoots<T: Config<I>, I: 'static = ()> = StorageMap<_, Twox64Concat, u32, H256>;
+
+	/// State roots of the source-chain headers finalized by the light client, by source block
+	/// number. Only burns are proven against them, balances need a snapshot root.
+	#[pallet::storage]
+	#[pallet::getter(fn finalized_source_root)]
+	pub type FinalizedSourceRoots<T: Config<I>, I: 'static = ()> = StorageMap<_, Twox64Concat, u32, H256>;
+
+	/// Source block of the state root each source account claimed its proven balance at. A
+	/// balance can only be claimed once, whatever the number of state roots.
+	#[pallet::storage]
+	#[pallet::getter(fn proven_claim)]
+	pub type ProvenClaims<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, u32>;
+
+	/// Number and hash of the latest source-chain header finalized by the light client.
+	#[pallet::storage]
+	#[pallet::getter(fn best_finalized_source_header)]
+	pub type BestFinalizedSourceHeader<T: Config<I>, I: 'static = ()> = StorageValue<_, (u32, H256)>;
+
+	/// Id of the current GRANDPA authority set of the source chain.
+	#[pallet::storage]
+	#[pallet::getter(fn source_authority_set_id)]
+	pub type SourceAuthoritySetId<T: Config<I>, I: 'static = ()> = StorageValue<_, u64, ValueQuery>;
+
+	/// Current GRANDPA authorities of the source chain.
+	#[pallet::storage]
+	#[pallet::getter(fn source_authorities)]
+	pub type SourceAuthorities<T: Config<I>, I: 'static = ()> = StorageValue<_, SourceAuthoritiesOf<T, I>, ValueQuery>;
+
+	/// Source-chain burns that were credited, by source block and index of the burn event.
+	#[pallet::storage]
+	pub type ClaimedBurns<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, u32, ()>;
+
+	/// Accounts allowed to add trusted Ethereum blocks.
+	#[pallet::storage]
+	pub type EthereumRelayers<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// Hashes of Ethereum blocks that ERC-20 burns can be proven against.
+	#[pallet::storage]
+	pub type TrustedEthereumBlocks<T: Config<I>, I: 'static = ()> = StorageMap<_, Identity, H256, ()>;
+
+	/// Receipts of ERC-20 burns that were credited, by block hash and transaction index.
+	#[pallet::storage]
+	pub type ClaimedReceipts<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Identity, H256, Twox64Concat, u32, ()>;
+
+	/// Current set of attesters.
+	#[pallet::storage]
+	#[pallet::getter(fn attesters)]
+	pub type Attesters<T: Config<I>, I: 'static = ()> = StorageValue<_, AttestersOf<T, I>, ValueQuery>;
+
+	/// Number of attesters of the current set that have to sign a migration.
+	#[pallet::storage]
+	#[pallet::getter(fn attester_threshold)]
+	pub type AttesterThreshold<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Version of the attester set, increased on every change.
+	#[pallet::storage]
+	#[pallet::getter(fn attester_set_id)]
+	pub type AttesterSetId<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Nonce of the next release requested on the source chain.
+	#[pallet::storage]
+	#[pallet::getter(fn outbound_nonce)]
+	pub type OutboundNonce<T: Config<I>, I: 'static = ()> = StorageValue<_, u64, ValueQuery>;
+
+	/// Releases on the source chain that the relayer has not confirmed yet, by nonce.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_release)]
+	pub type PendingReleases<T: Config<I>, I: 'static = ()> = StorageMap<_, Twox64Concat, u64, OutboundRelease<T::AccountId, T::SourceAccountId, BalanceOf<T, I>>>;
+
+	/// Maximum total amount of pending releases. Moving tokens back is disabled without a cap.
+	#[pallet::storage]
+	#[pallet::getter(fn outbound_cap)]
+	pub type OutboundCap<T: Config<I>, I: 'static = ()> = StorageValue<_, BalanceOf<T, I>>;
+
+	/// Total amount of the pending releases.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_outbound)]
+	pub type PendingOutbound<T: Config<I>, I: 'static = ()> = StorageValue<_, BalanceOf<T, I>, ValueQuery>;
+
+	/// Migrations waiting to be executed in `on_idle`, by queue index.
+	#[pallet::storage]
+	#[pallet::getter(fn queued_migration)]
+	pub type MigrationQueue<T: Config<I>, I: 'static = ()> = StorageMap<_, Twox64Concat, u32, QueuedMigrationOf<T, I>>;
+
+	/// Index of the next queued migration to execute.
+	#[pallet::storage]
+	#[pallet::getter(fn migration_queue_head)]
+	pub type MigrationQueueHead<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Index the next enqueued migration gets.
+	#[pallet::storage]
+	#[pallet::getter(fn migration_queue_tail)]
+	pub type MigrationQueueTail<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Queued migrations that failed when executed, by queue index.
+	#[pallet::storage]
+	#[pallet::getter(fn failed_migration)]
+	pub type FailedMigrations<T: Config<I>, I: 'static = ()> = StorageMap<_, Twox64Concat, u32, QueuedMigrationOf<T, I>>;
+
+	/// Linear vesting of the tokens migrated from a category: nothing is transferable before
+	/// `start`, everything after `start + duration`.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct CategoryVesting<BlockNumber> {
+		pub start: BlockNumber,
+		pub duration: BlockNumber,
+	}
+
+	/// Part of the source supply migrated out of its own sub-vault.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct AllocationCategory<AccountId, Balance, BlockNumber> {
+		/// Sub-vault the category is migrated from, see [`Pallet::category_vault`].
+		pub vault: AccountId,
+		/// Maximum total amount migrated from the category.
+		pub cap: Balance,
+		/// Total amount migrated from the category so far.
+		pub migrated: Balance,
+		/// Vesting applied to every amount migrated from the category.
+		pub vesting: Option<CategoryVesting<BlockNumber>>,
+	}
+
+	/// Allocation categories by name.
+	#[pallet::storage]
+	#[pallet::getter(fn category)]
+	pub type Categories<T: Config<I>, I: 'static = ()> = CountedStorageMap<_, Blake2_128Concat, CategoryName<T, I>, AllocationCategoryOf<T, I>>;
+
+	/// Sub-vaults of the allocation categories.
+	#[pallet::storage]
+	pub type CategoryVaults<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// Amounts migrated to an account from each category, frozen while the category vests.
+	#[pallet::storage]
+	#[pallet::getter(fn category_amount)]
+	pub type CategoryAmounts<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, CategoryName<T, I>, BalanceOf<T, I>, ValueQuery>;
+
+	/// The only account migrations for a source account may credit, bound by its holder.
+	#[pallet::storage]
+	#[pallet::getter(fn bound_destination)]
+	pub type BoundDestinations<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, T::AccountId>;
+
+	/// Number of destinations bound for a source account so far.
+	#[pallet::storage]
+	#[pallet::getter(fn destination_binding_nonce)]
+	pub type DestinationBindingNonces<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, u32, ValueQuery>;
+
+	/// Total amount migrated for a source account to each account it credited.
+	#[pallet::storage]
+	#[pallet::getter(fn credited)]
+	pub type Credits<T: Config<I>, I: 'static = ()> = StorageDoubleMap<_, Blake2_128Concat, T::SourceAccountId, Blake2_128Concat, T::AccountId, BalanceOf<T, I>, ValueQuery>;
+
+	/// Resolution of a dispute.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum DisputeStatus<AccountId, Balance, BlockNumber> {
+		Open,
+		/// The credits were right and are no longer frozen.
+		Released { at: BlockNumber },
+		/// `amount` of the credits was moved to `to`, `shortfall` could not be recovered.
+		Redirected { at: BlockNumber, to: AccountId, amount: Balance, shortfall: Balance },
+	}
+
+	/// Claim that the migrations for a source account credited the wrong accounts.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Dispute<SourceAccountId, AccountId, Balance, BlockNumber, Hash, Credits> {
+		pub for_account: SourceAccountId,
+		pub disputer: AccountId,
+		pub bond: Balance,
+		/// Hash of the evidence, kept off chain.
+		pub evidence_hash: Hash,
+		pub opened: BlockNumber,
+		/// Accounts credited for `for_account` and the amounts frozen in them.
+		pub credits: Credits,
+		pub status: DisputeStatus<AccountId, Balance, BlockNumber>,
+	}
+
+	/// Number of disputes opened for a source account so far.
+	#[pallet::storage]
+	#[pallet::getter(fn dispute_nonce)]
+	pub type DisputeNonces<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, u32, ValueQuery>;
+
+	/// Id the next dispute gets.
+	#[pallet::storage]
+	#[pallet::getter(fn next_dispute_id)]
+	pub type NextDisputeId<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Open and resolved disputes by id.
+	#[pallet::storage]
+	#[pallet::getter(fn dispute_info)]
+	pub type Disputes<T: Config<I>, I: 'static = ()> = StorageMap<_, Twox64Concat, u32, DisputeOf<T, I>>;
+
+	/// Id of the open dispute of a source account.
+	#[pallet::storage]
+	#[pallet::getter(fn open_dispute)]
+	pub type OpenDisputes<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::SourceAccountId, u32>;
+
+	/// Migrated balance of an account frozen by open disputes.
+	#[pallet::storage]
+	#[pallet::getter(fn disputed_balance)]
+	pub type DisputedBalances<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T, I>, ValueQuery>;
 
 	#[pallet::genesis_config]
-	pub struct GenesisConfig<T: Config> {
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		pub migration_vault_account: Option<T::AccountId>,
 		pub migration_owner: Option<T::AccountId>,
-		pub asset_id: Option<T::TokenId>
+		pub asset_id: Option<T::TokenId>,
+		pub transfer_lock_until: Option<T::BlockNumber>,
+		pub source_state_roots: Vec<(u32, H256)>,
 	}
 
 	#[cfg(feature = "std")]
-	impl<T: Config> Default for GenesisConfig<T> {
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
 		fn default() -> Self {
 			Self { 
 				migration_vault_account: Option::None,
 				migration_owner: Option::None,
-				asset_id: Option::None
+				asset_id: Option::None,
+				transfer_lock_until: Option::None,
+				source_state_roots: Vec::new(),
 			}
 		}
 	}
 
 	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
 		fn build(&self) {
 			let tmp1 = self.migration_vault_account.clone();
 			match tmp1 {
-				Some(a) => <MigrationVaultAccount<T>>::put(a),
-				None => debug(&Error::<T>::MigrationVaultAccountNoValue),
+				Some(a) => <MigrationVaultAccount<T, I>>::put(a),
+				None => debug(&Error::<T, I>::MigrationVaultAccountNoValue),
 			}
 
 			let tmp2 = self.migration_owner.clone();
 			match tmp2 {
-				Some(a) => <MigrationOwner<T>>::put(a),
-				None => debug(&Error::<T>::MigrationOwnerNoValue),
+				Some(a) => <MigrationOwner<T, I>>::put(a),
+				None => debug(&Error::<T, I>::MigrationOwnerNoValue),
 			}
 
 			let tmp3 = self.asset_id.clone();
 			match tmp3 {
-				Some(a) => <TokenId<T>>::put(a),
-				None => debug(&Error::<T>::AssetIdNoValue),
+				Some(a) => <TokenId<T, I>>::put(a),
+				None => debug(&Error::<T, I>::AssetIdNoValue),
+			}
+
+			if let Some(until) = self.transfer_lock_until {
+				<TransferLockUntil<T, I>>::put(until);
+			}
+
+			for (block, root) in &self.source_state_roots {
+				<SourceStateRoots<T, I>>::insert(block, root);
+			}
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config<I>, I: 'static> ValidateUnsigned for Pallet<T, I> {
+		type Call = Call<T, I>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::migrate_attested { for_account, account_to_credit, amount, source_ref, category, attestations } = call {
+				if <ProcessedSourceRefs<T, I>>::contains_key(source_ref) {
+					return Err(InvalidTransaction::Stale.into())
+				}
+				Self::check_attestations(source_ref, for_account, account_to_credit, *amount, category.as_ref(), attestations)
+					.map_err(|_| InvalidTransaction::BadProof)?;
+
+				ValidTransaction::with_tag_prefix("MigrationAttested")
+					.priority(T::MigrationTxPriority::get())
+					.and_provides((T::PalletId::get(), source_ref))
+					.longevity(T::MigrationTxLongevity::get())
+					.propagate(true)
+					.build()
+			} else {
+				Err(InvalidTransaction::Call.into())
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			match <TransferLockUntil<T, I>>::get() {
+				Some(until) if now >= until => {
+					<TransferLockUntil<T, I>>::kill();
+					Self::deposit_event(Event::TransferLockLifted);
+					T::WeightInfo::lift_transfer_lock()
+				},
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+
+		fn on_idle(_now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::process_migration_queue(remaining_weight)
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			match Self::process_offchain_queue(now) {
+				Ok(0) => {},
+				Ok(submitted) => log::info!(
+					target: "runtime::migration",
+					"submitted {} queued migrations",
+					submitted,
+				),
+				Err(e) => log::debug!(
+					target: "runtime::migration",
+					"skipping migration queue: {:?}",
+					e,
+				),
 			}
 		}
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
+	pub enum Event<T: Config<I>, I: 'static = ()> {
 		BalanceMigrated { 
-			amount: BalanceOf<T>,
+			amount: BalanceOf<T, I>,
 			from_vault: T::AccountId, 
-			for_account: [u8; 32], 
+			for_account: T::SourceAccountId, 
 			to_account: T::AccountId,
-			vault_balance_remained: BalanceOf<T>,
-			account_balance_after: BalanceOf<T>,
+			vault_balance_remained: BalanceOf<T, I>,
+			account_balance_after: BalanceOf<T, I>,
+			source_ref: Option<SourceRef<T, I>>,
+		},
+		AllocationsCommitted {
+			root: T::Hash,
+			entries: u32,
+		},
+		AllocationsUploaded {
+			count: u32,
+			uploaded: u32,
+		},
+		AllocationsSealed {
+			root: T::Hash,
+			entries: u32,
+		},
+		TransferLockSet {
+			until: T::BlockNumber,
+		},
+		TransferLockLifted,
+		FeeRateSet {
+			rate: Option<FixedU128>,
+		},
+		SponsorAdded {
+			sponsor: T::AccountId,
+		},
+		SponsorRemoved {
+			sponsor: T::AccountId,
+		},
+		Sponsored {
+			sponsor: T::AccountId,
+			who: T::AccountId,
+			cap: BalanceOf<T, I>,
+		},
+		SponsorshipRemoved {
+			sponsor: T::AccountId,
+			who: T::AccountId,
+		},
+		SourceStateRootSet {
+			block: u32,
+			root: Option<H256>,
+		},
+		StorageProofClaimed {
+			block: u32,
+			for_account: T::SourceAccountId,
+			amount: BalanceOf<T, I>,
+		},
+		SourceHeaderFinalized {
+			number: u32,
+			hash: H256,
+		},
+		SourceAuthoritySetChanged {
+			set_id: u64,
+		},
+		BurnClaimed {
+			block: u32,
+			event_index: u32,
+			for_account: T::SourceAccountId,
+			amount: BalanceOf<T, I>,
+		},
+		RelayerAdded {
+			relayer: T::AccountId,
+		},
+		RelayerRemoved {
+			relayer: T::AccountId,
+		},
+		EthereumBlockTrusted {
+			block_hash: H256,
+			trusted: bool,
+		},
+		Erc20BurnClaimed {
+			block_hash: H256,
+			tx_index: u32,
+			for_account: T::SourceAccountId,
+			amount: BalanceOf<T, I>,
+		},
+		AttestersSet {
+			set_id: u32,
+			threshold: u32,
+		},
+		MigrationAttested {
+			set_id: u32,
+			source_ref: SourceRef<T, I>,
+		},
+		OutboundCapSet {
+			cap: Option<BalanceOf<T, I>>,
+		},
+		MigratedBack {
+			nonce: u64,
+			who: T::AccountId,
+			to: T::SourceAccountId,
+			amount: BalanceOf<T, I>,
+		},
+		ReleaseConfirmed {
+			nonce: u64,
+		},
+		MigrationsQueued {
+			first: u32,
+			count: u32,
+		},
+		QueuedMigrationFailed {
+			index: u32,
+			error: DispatchError,
+		},
+		CategorySet {
+			name: CategoryName<T, I>,
+			vault: T::AccountId,
+			cap: BalanceOf<T, I>,
+			vesting: Option<CategoryVesting<T::BlockNumber>>,
+		},
+		DestinationRegistered {
+			for_account: T::SourceAccountId,
+			destination: T::AccountId,
+		},
+		DisputeOpened {
+			id: u32,
+			for_account: T::SourceAccountId,
+			disputer: T::AccountId,
+			evidence_hash: T::Hash,
+		},
+		DisputeReleased {
+			id: u32,
+		},
+		DisputeRedirected {
+			id: u32,
+			to: T::AccountId,
+			amount: BalanceOf<T, I>,
+			shortfall: BalanceOf<T, I>,
 		},
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
-	pub enum Error<T> {
+	pub enum Error<T, I = ()> {
 		Unauthorised,
 		MigrationVaultAccountNoValue,
 		MigrationOwnerNoValue,
-		AssetIdNoValue
+		AssetIdNoValue,
+		SourceRefAlreadyUsed,
+		SourceRefTooLong,
+		AllocationsAlreadyCommitted,
+		AllocationsNotCommitted,
+		AllocationsAlreadySealed,
+		AllocationsNotSealed,
+		TooManyAllocations,
+		DuplicateAllocation,
+		AllocationCommitmentMismatch,
+		NoAllocation,
+		AllocationExceeded,
+		FeeRateNotSet,
+		NotMigratedAsset,
+		NotSponsor,
+		NotMigrationRecipient,
+		SponsoredByOther,
+		NotSponsored,
+		UnknownSourceStateRoot,
+		InvalidStorageProof,
+		NoProvenBalance,
+		AlreadyClaimed,
+		LightClientNotInitialized,
+		InvalidJustification,
+		OldSourceHeader,
+		UnsupportedAuthoritySetChange,
+		TooManySourceAuthorities,
+		NoSuchSourceEvent,
+		NotABurn,
+		BurnAlreadyClaimed,
+		Erc20BurnsDisabled,
+		NotRelayer,
+		UntrustedEthereumBlock,
+		InvalidEthereumHeader,
+		InvalidReceiptProof,
+		NoBurnInReceipt,
+		ReceiptAlreadyClaimed,
+		InvalidAttesterThreshold,
+		DuplicateAttester,
+		NoAttesters,
+		InvalidAttestation,
+		InsufficientAttestations,
+		OutboundDisabled,
+		OutboundCapExceeded,
+		NothingToRelease,
+		UnknownRelease,
+		MigrationQueueFull,
+		UnknownCategory,
+		TooManyCategories,
+		CategoryCapExceeded,
+		CategoryCapBelowMigrated,
+		InvalidSourceSignature,
+		DestinationMismatch,
+		SourceAccountDisputed,
+		DisputeAlreadyOpen,
+		NothingCredited,
+		TooManyCredits,
+		UnknownDispute,
+		DisputeNotOpen,
+		TransfersLocked,
+		UploadedAllocationsUnderstated,
 	}
 	
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		
+		/// Transfer `amount` from the vault to `account_to_credit` for `for_account`.
+		///
+		/// With a `category`, the amount is taken from the sub-vault of that category instead,
+		/// within its cap and with its vesting.
+		///
+		/// Dispatched in the operational class. Successful calls by the migration owner are
+		/// free, everyone else pays the full fee.
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::migrate())]
-		pub fn migrate(origin: OriginFor<T>, for_account: [u8; 32], account_to_credit: T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		#[pallet::weight((T::WeightInfo::migrate().saturating_add(T::OnMigrated::weight()), DispatchClass::Operational))]
+		pub fn migrate(origin: OriginFor<T>, for_account: T::SourceAccountId, account_to_credit: T::AccountId, amount: BalanceOf<T, I>, source_ref: Option<SourceRef<T, I>>, category: Option<CategoryName<T, I>>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			ensure!( <MigrationVaultAccount<T>>::exists(),
-				Error::<T>::MigrationVaultAccountNoValue
+			Self::do_migrate_from(&who, for_account, account_to_credit, amount, source_ref, category)?;
+			Ok(Pays::No.into())
+		}
+
+		/// Commit to the allocation table before uploading it.
+		///
+		/// `root` is the hash chain over all `entries` as computed by
+		/// [`Pallet::allocation_commitment`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::commit_allocations())]
+		pub fn commit_allocations(origin: OriginFor<T>, root: T::Hash, entries: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_owner(&who)?;
+
+			ensure!(!<AllocationCommitment<T, I>>::exists(), Error::<T, I>::AllocationsAlreadyCommitted);
+
+			<AllocationCommitment<T, I>>::put(AllocationCommitmentInfo { root, entries });
+			<AllocationUpload<T, I>>::kill();
+
+			Self::deposit_event(Event::AllocationsCommitted { root, entries });
+			Ok(())
+		}
+
+		/// Replace the commitment to an allocation table that is not sealed yet, e.g. after a
+		/// wrong `root` or `entries` count, or a table with a duplicate source.
+		///
+		/// Clears the entries uploaded so far. `uploaded` must be at least their number.
+		#[pallet::call_index(29)]
+		#[pallet::weight(T::WeightInfo::recommit_allocations(*uploaded))]
+		pub fn recommit_allocations(origin: OriginFor<T>, root: T::Hash, entries: u32, uploaded: u32) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			ensure!(<AllocationCommitment<T, I>>::exists(), Error::<T, I>::AllocationsNotCommitted);
+			ensure!(!Self::allocations_sealed(), Error::<T, I>::AllocationsAlreadySealed);
+			let progress = <AllocationUpload<T, I>>::get();
+			ensure!(uploaded >= progress.uploaded, Error::<T, I>::UploadedAllocationsUnderstated);
+
+			let _ = <Allocations<T, I>>::clear(progress.uploaded, None);
+			<AllocationUpload<T, I>>::kill();
+			<AllocationCommitment<T, I>>::put(AllocationCommitmentInfo { root, entries });
+
+			Self::deposit_event(Event::AllocationsCommitted { root, entries });
+			Ok(Some(T::WeightInfo::recommit_allocations(progress.uploaded)).into())
+		}
+
+		/// Upload the next chunk of the committed allocation table.
+		///
+		/// Chunks must be uploaded in the order the commitment was computed over.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::upload_allocations(chunk.len() as u32))]
+		pub fn upload_allocations(origin: OriginFor<T>, chunk: AllocationChunk<T, I>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_owner(&who)?;
+
+			let commitment = <AllocationCommitment<T, I>>::get().ok_or(Error::<T, I>::AllocationsNotCommitted)?;
+			ensure!(!Self::allocations_sealed(), Error::<T, I>::AllocationsAlreadySealed);
+
+			let mut progress = <AllocationUpload<T, I>>::get();
+			let count = chunk.len() as u32;
+			ensure!(
+				progress.uploaded.saturating_add(count) <= commitment.entries,
+				Error::<T, I>::TooManyAllocations
 			);
 
-			ensure!(<MigrationOwner<T>>::exists(),
-				Error::<T>::MigrationOwnerNoValue
+			for (source, amount) in chunk {
+				ensure!(!<Allocations<T, I>>::contains_key(&source), Error::<T, I>::DuplicateAllocation);
+				progress.accumulator = Self::next_allocation_accumulator(progress.accumulator, &source, &amount);
+				<Allocations<T, I>>::insert(source, amount);
+			}
+			progress.uploaded += count;
+			<AllocationUpload<T, I>>::put(&progress);
+
+			Self::deposit_event(Event::AllocationsUploaded { count, uploaded: progress.uploaded });
+			Ok(())
+		}
+
+		/// Seal the allocation table once the uploaded entries match the commitment.
+		///
+		/// From then on `migrate` never credits a source account more than its allocation.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::seal_allocations())]
+		pub fn seal_allocations(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_owner(&who)?;
+
+			let commitment = <AllocationCommitment<T, I>>::get().ok_or(Error::<T, I>::AllocationsNotCommitted)?;
+			ensure!(!Self::allocations_sealed(), Error::<T, I>::AllocationsAlreadySealed);
+
+			let progress = <AllocationUpload<T, I>>::get();
+			ensure!(
+				progress.uploaded == commitment.entries && progress.accumulator == commitment.root,
+				Error::<T, I>::AllocationCommitmentMismatch
 			);
 
-			ensure!(<TokenId<T>>::exists(),
-				Error::<T>::AssetIdNoValue
+			<AllocationsSealed<T, I>>::put(true);
+
+			Self::deposit_event(Event::AllocationsSealed { root: commitment.root, entries: commitment.entries });
+			Ok(())
+		}
+
+		/// Lock transfers of migrated tokens until block `until`, or lift the lock with `None`.
+		///
+		/// The lock is lifted automatically at the start of block `until`. Migrations out of the
+		/// vault are not affected.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::set_transfer_lock())]
+		pub fn set_transfer_lock(origin: OriginFor<T>, until: Option<T::BlockNumber>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			match until {
+				Some(until) => {
+					<TransferLockUntil<T, I>>::put(until);
+					Self::deposit_event(Event::TransferLockSet { until });
+				},
+				None => {
+					<TransferLockUntil<T, I>>::kill();
+					Self::deposit_event(Event::TransferLockLifted);
+				},
+			}
+			Ok(())
+		}
+
+		/// Set how many units of the migrated asset are charged per unit of the local currency
+		/// when fees are paid in the migrated asset, or disallow paying fees in it with `None`.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_fee_rate())]
+		pub fn set_fee_rate(origin: OriginFor<T>, rate: Option<FixedU128>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			<FeeRate<T, I>>::set(rate);
+			Self::deposit_event(Event::FeeRateSet { rate });
+			Ok(())
+		}
+
+		/// Allow `sponsor` to pay the fees of migration recipients.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::add_sponsor())]
+		pub fn add_sponsor(origin: OriginFor<T>, sponsor: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			<Sponsors<T, I>>::insert(&sponsor, ());
+			Self::deposit_event(Event::SponsorAdded { sponsor });
+			Ok(())
+		}
+
+		/// Stop `sponsor` from paying fees. Its sponsorships are no longer used.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::remove_sponsor())]
+		pub fn remove_sponsor(origin: OriginFor<T>, sponsor: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			ensure!(<Sponsors<T, I>>::contains_key(&sponsor), Error::<T, I>::NotSponsor);
+			<Sponsors<T, I>>::remove(&sponsor);
+			Self::deposit_event(Event::SponsorRemoved { sponsor });
+			Ok(())
+		}
+
+		/// Pay the fees of calls to `pallets` sent by the migration recipient `who`, up to `cap`
+		/// in total. Replaces an earlier sponsorship of `who` by the same sponsor.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::sponsor())]
+		pub fn sponsor(origin: OriginFor<T>, who: T::AccountId, cap: BalanceOf<T, I>, pallets: SponsoredPallets<T, I>) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+
+			ensure!(<Sponsors<T, I>>::contains_key(&sponsor), Error::<T, I>::NotSponsor);
+			ensure!(<MigrationRecipients<T, I>>::contains_key(&who), Error::<T, I>::NotMigrationRecipient);
+			if let Some(existing) = <Sponsorships<T, I>>::get(&who) {
+				ensure!(existing.sponsor == sponsor, Error::<T, I>::SponsoredByOther);
+			}
+
+			<Sponsorships<T, I>>::insert(&who, Sponsorship { sponsor: sponsor.clone(), remaining: cap, pallets });
+			Self::deposit_event(Event::Sponsored { sponsor, who, cap });
+			Ok(())
+		}
+
+		/// Stop paying the fees of `who`.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::unsponsor())]
+		pub fn unsponsor(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+
+			let existing = <Sponsorships<T, I>>::get(&who).ok_or(Error::<T, I>::NotSponsored)?;
+			ensure!(existing.sponsor == sponsor, Error::<T, I>::SponsoredByOther);
+			<Sponsorships<T, I>>::remove(&who);
+			Self::deposit_event(Event::SponsorshipRemoved { sponsor, who });
+			Ok(())
+		}
+
+		/// Trust the source-chain state `root` at source block `block`, or stop trusting it with
+		/// `None`.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::set_source_state_root())]
+		pub fn set_source_state_root(origin: OriginFor<T>, block: u32, root: Option<H256>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			<SourceStateRoots<T, I>>::set(block, root);
+			Self::deposit_event(Event::SourceStateRootSet { block, root });
+			Ok(())
+		}
+
+		/// Credit `account_to_credit` with the balance of `for_account` proven by `proof` against
+		/// the source-chain state root at source block `block`.
+		///
+		/// The migration owner still picks the account to credit, but the amount is taken from
+		/// the proof. Each source account can claim once.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::claim_with_storage_proof(proof.len() as u32).saturating_add(T::OnMigrated::weight()))]
+		pub fn claim_with_storage_proof(origin: OriginFor<T>, block: u32, for_account: T::SourceAccountId, account_to_credit: T::AccountId, proof: StorageProofOf<T, I>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_owner(&who)?;
+
+			let root = <SourceStateRoots<T, I>>::get(block).ok_or(Error::<T, I>::UnknownSourceStateRoot)?;
+			ensure!(!<ProvenClaims<T, I>>::contains_key(&for_account), Error::<T, I>::AlreadyClaimed);
+			let amount = Self::prove_source_balance(&root, &for_account, proof.into_inner())?;
+
+			<ProvenClaims<T, I>>::insert(&for_account, block);
+			Self::do_migrate(&who, for_account.clone(), account_to_credit, amount, None)?;
+			Self::deposit_event(Event::StorageProofClaimed { block, for_account, amount });
+			Ok(())
+		}
+
+		/// Start the source-chain light client from the trusted `header`, finalized by
+		/// `authorities` of set `set_id`.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::initialize_light_client())]
+		pub fn initialize_light_client(origin: OriginFor<T>, header: SourceHeader, set_id: u64, authorities: SourceAuthoritiesOf<T, I>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::finalize_source_header(&header);
+			<SourceAuthoritySetId<T, I>>::put(set_id);
+			<SourceAuthorities<T, I>>::put(authorities);
+			Self::deposit_event(Event::SourceAuthoritySetChanged { set_id });
+			Ok(())
+		}
+
+		/// Finalize the source-chain `header` with its SCALE encoded GRANDPA `justification`.
+		///
+		/// Anyone can relay headers. The header must be newer than the best finalized one and
+		/// headers changing the authority set must not be skipped.
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::submit_finality_proof(T::MaxSourceAuthorities::get()))]
+		pub fn submit_finality_proof(origin: OriginFor<T>, header: SourceHeader, justification: Vec<u8>) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let (best_number, _) = <BestFinalizedSourceHeader<T, I>>::get().ok_or(Error::<T, I>::LightClientNotInitialized)?;
+			ensure!(header.number > best_number, Error::<T, I>::OldSourceHeader);
+
+			let justification = GrandpaJustification::decode(&mut &justification[..])
+				.map_err(|_| Error::<T, I>::InvalidJustification)?;
+			let set_id = <SourceAuthoritySetId<T, I>>::get();
+			Self::verify_justification(&header, &justification, set_id, &<SourceAuthorities<T, I>>::get())?;
+
+			if let Some(next_authorities) = Self::scheduled_authority_change(&header)? {
+				let next_authorities: SourceAuthoritiesOf<T, I> = next_authorities
+					.try_into()
+					.map_err(|_| Error::<T, I>::TooManySourceAuthorities)?;
+				<SourceAuthorities<T, I>>::put(next_authorities);
+				<SourceAuthoritySetId<T, I>>::put(set_id + 1);
+				Self::deposit_event(Event::SourceAuthoritySetChanged { set_id: set_id + 1 });
+			}
+			Self::finalize_source_header(&header);
+
+			let precommits = justification.commit.precommits.len() as u32;
+			Ok(Some(T::WeightInfo::submit_finality_proof(precommits)).into())
+		}
+
+		/// Credit the burn in the event at `event_index` of source block `block`, proven by
+		/// `proof` of that block's `System::Events`.
+		///
+		/// Anyone can submit burns, the amount and the account to credit are taken from the
+		/// event. Each burn is credited once.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::claim_burn(proof.len() as u32).saturating_add(T::OnMigrated::weight()))]
+		pub fn claim_burn(origin: OriginFor<T>, block: u32, event_index: u32, proof: StorageProofOf<T, I>) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let root = <FinalizedSourceRoots<T, I>>::get(block).ok_or(Error::<T, I>::UnknownSourceStateRoot)?;
+			ensure!(!<ClaimedBurns<T, I>>::contains_key(block, event_index), Error::<T, I>::BurnAlreadyClaimed);
+			let event = Self::prove_source_event(&root, event_index, proof.into_inner())?;
+			let (for_account, amount, account_to_credit) = T::SourceBurns::burn(&event).ok_or(Error::<T, I>::NotABurn)?;
+			let amount: BalanceOf<T, I> = amount.try_into().map_err(|_| ArithmeticError::Overflow)?;
+
+			<ClaimedBurns<T, I>>::insert(block, event_index, ());
+			// The proven burn stands in for the authorisation of the migration owner.
+			let owner = <MigrationOwner<T, I>>::get().ok_or(Error::<T, I>::MigrationOwnerNoValue)?;
+			Self::do_migrate(&owner, for_account.clone(), account_to_credit, amount, None)?;
+			Self::deposit_event(Event::BurnClaimed { block, event_index, for_account, amount });
+			Ok(())
+		}
+
+		/// Allow `relayer` to add trusted Ethereum blocks.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::add_relayer())]
+		pub fn add_relayer(origin: OriginFor<T>, relayer: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			<EthereumRelayers<T, I>>::insert(&relayer, ());
+			Self::deposit_event(Event::RelayerAdded { relayer });
+			Ok(())
+		}
+
+		/// Stop `relayer` from adding trusted Ethereum blocks. Blocks it added stay trusted.
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::remove_relayer())]
+		pub fn remove_relayer(origin: OriginFor<T>, relayer: T::AccountId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			ensure!(<EthereumRelayers<T, I>>::contains_key(&relayer), Error::<T, I>::NotRelayer);
+			<EthereumRelayers<T, I>>::remove(&relayer);
+			Self::deposit_event(Event::RelayerRemoved { relayer });
+			Ok(())
+		}
+
+		/// Trust the Ethereum block `block_hash` once it is final, or stop trusting it.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::set_trusted_ethereum_block())]
+		pub fn set_trusted_ethereum_block(origin: OriginFor<T>, block_hash: H256, trusted: bool) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(<EthereumRelayers<T, I>>::contains_key(&who), Error::<T, I>::NotRelayer);
+
+			if trusted {
+				<TrustedEthereumBlocks<T, I>>::insert(block_hash, ());
+			} else {
+				<TrustedEthereumBlocks<T, I>>::remove(block_hash);
+			}
+			Self::deposit_event(Event::EthereumBlockTrusted { block_hash, trusted });
+			Ok(())
+		}
+
+		/// Credit `account_to_credit` with the ERC-20 burn in `receipt`, the RLP encoded receipt
+		/// of transaction `tx_index` of the trusted Ethereum block with RLP encoded `header`.
+		///
+		/// `proof` holds the nodes of the receipts trie from its root to the receipt. Anyone can
+		/// submit burns, the amount is the sum of the `Transfer`s to the burn address of
+		/// `account_to_credit`, which all have to be from the same holder. Each receipt is
+		/// credited once.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::claim_erc20_burn(proof.len() as u32, receipt.len() as u32).saturating_add(T::OnMigrated::weight()))]
+		pub fn claim_erc20_burn(
+			origin: OriginFor<T>,
+			header: Vec<u8>,
+			tx_index: u32,
+			receipt: ReceiptOf<T, I>,
+			proof: StorageProofOf<T, I>,
+			account_to_credit: T::AccountId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let token = T::Erc20Source::token().ok_or(Error::<T, I>::Erc20BurnsDisabled)?;
+
+			let block_hash = H256(sp_io::hashing::keccak_256(&header));
+			ensure!(<TrustedEthereumBlocks<T, I>>::contains_key(block_hash), Error::<T, I>::UntrustedEthereumBlock);
+			ensure!(!<ClaimedReceipts<T, I>>::contains_key(block_hash, tx_index), Error::<T, I>::ReceiptAlreadyClaimed);
+
+			let receipts_root = Self::receipts_root(&header).ok_or(Error::<T, I>::InvalidEthereumHeader)?;
+			let key = rlp::encode(&tx_index);
+			let proven = Self::read_ethereum_trie(&receipts_root, &key, &proof).ok_or(Error::<T, I>::InvalidReceiptProof)?;
+			ensure!(proven == receipt.as_slice(), Error::<T, I>::InvalidReceiptProof);
+
+			let burn_address = Self::erc20_burn_address(&account_to_credit);
+			let (holder, amount) = Self::erc20_burn(&receipt, &token, &burn_address).ok_or(Error::<T, I>::NoBurnInReceipt)?;
+			let for_account = T::Erc20Source::holder(holder).ok_or(Error::<T, I>::Erc20BurnsDisabled)?;
+			let amount: BalanceOf<T, I> = amount.try_into().map_err(|_| ArithmeticError::Overflow)?;
+
+			<ClaimedReceipts<T, I>>::insert(block_hash, tx_index, ());
+			// The proven burn stands in for the authorisation of the migration owner.
+			let owner = <MigrationOwner<T, I>>::get().ok_or(Error::<T, I>::MigrationOwnerNoValue)?;
+			Self::do_migrate(&owner, for_account.clone(), account_to_credit, amount, None)?;
+			Self::deposit_event(Event::Erc20BurnClaimed { block_hash, tx_index, for_account, amount });
+			Ok(())
+		}
+
+		/// Replace the attesters by `attesters`, of which `threshold` have to sign a migration.
+		/// Signatures of earlier sets are no longer accepted.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::set_attesters(attesters.len() as u32))]
+		pub fn set_attesters(origin: OriginFor<T>, attesters: AttestersOf<T, I>, threshold: u32) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			ensure!(threshold > 0 && threshold as usize <= attesters.len(), Error::<T, I>::InvalidAttesterThreshold);
+			let mut unique = attesters.to_vec();
+			unique.sort();
+			unique.dedup();
+			ensure!(unique.len() == attesters.len(), Error::<T, I>::DuplicateAttester);
+
+			let set_id = <AttesterSetId<T, I>>::get().saturating_add(1);
+			<Attesters<T, I>>::put(attesters);
+			<AttesterThreshold<T, I>>::put(threshold);
+			<AttesterSetId<T, I>>::put(set_id);
+			Self::deposit_event(Event::AttestersSet { set_id, threshold });
+			Ok(())
+		}
+
+		/// Migrate `amount` for the transfer `source_ref` of `for_account` on the source chain,
+		/// as signed by the attesters in `attestations`, from the sub-vault of `category` if
+		/// given.
+		///
+		/// Submitted as an unsigned transaction, so neither the submitter nor the recipient pays
+		/// fees. Each `source_ref` is migrated once.
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::migrate_attested(attestations.len() as u32).saturating_add(T::OnMigrated::weight()))]
+		pub fn migrate_attested(
+			origin: OriginFor<T>,
+			for_account: T::SourceAccountId,
+			account_to_credit: T::AccountId,
+			amount: BalanceOf<T, I>,
+			source_ref: SourceRef<T, I>,
+			category: Option<CategoryName<T, I>>,
+			attestations: AttestationsOf<T, I>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			Self::check_attestations(&source_ref, &for_account, &account_to_credit, amount, category.as_ref(), &attestations)?;
+
+			// The attesters stand in for the authorisation of the migration owner.
+			let owner = <MigrationOwner<T, I>>::get().ok_or(Error::<T, I>::MigrationOwnerNoValue)?;
+			Self::do_migrate_from(&owner, for_account, account_to_credit, amount, Some(source_ref.clone()), category)?;
+			Self::deposit_event(Event::MigrationAttested { set_id: <AttesterSetId<T, I>>::get(), source_ref });
+			Ok(())
+		}
+
+		/// Set the maximum total amount of pending releases on the source chain, or disable
+		/// moving tokens back with `None`.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::set_outbound_cap())]
+		pub fn set_outbound_cap(origin: OriginFor<T>, cap: Option<BalanceOf<T, I>>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			<OutboundCap<T, I>>::set(cap);
+			Self::deposit_event(Event::OutboundCapSet { cap });
+			Ok(())
+		}
+
+		/// Move `amount` of the migrated asset back to the vault and request its release to `to`
+		/// on the source chain.
+		///
+		/// This is one-way for the migration records: the tokens can't be traced back to the
+		/// source account or category they were migrated from, so `MigratedAmounts`, `Credits`
+		/// and the migrated totals of categories keep counting them. Releases are accounted for
+		/// in `PendingReleases` and `PendingOutbound` only.
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::migrate_back())]
+		pub fn migrate_back(origin: OriginFor<T>, to: T::SourceAccountId, amount: BalanceOf<T, I>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let cap = <OutboundCap<T, I>>::get().ok_or(Error::<T, I>::OutboundDisabled)?;
+			ensure!(!amount.is_zero(), Error::<T, I>::NothingToRelease);
+			let pending = <PendingOutbound<T, I>>::get().checked_add(&amount).ok_or(ArithmeticError::Overflow)?;
+			ensure!(pending <= cap, Error::<T, I>::OutboundCapExceeded);
+
+			let vault = <MigrationVaultAccount<T, I>>::get().ok_or(Error::<T, I>::MigrationVaultAccountNoValue)?;
+			let asset_id = <TokenId<T, I>>::get().ok_or(Error::<T, I>::AssetIdNoValue)?;
+			T::Assets::transfer(asset_id, &who, &vault, <T as ConfigHelper<I>>::currency_to_asset(amount), false)?;
+
+			let nonce = <OutboundNonce<T, I>>::get();
+			<OutboundNonce<T, I>>::put(nonce + 1);
+			<PendingReleases<T, I>>::insert(nonce, OutboundRelease { who: who.clone(), to: to.clone(), amount });
+			<PendingOutbound<T, I>>::put(pending);
+			Self::deposit_event(Event::MigratedBack { nonce, who, to, amount });
+			Ok(())
+		}
+
+		/// Confirm that the release `nonce` was executed on the source chain.
+		///
+		/// The origin must be the migration owner.
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::confirm_release())]
+		pub fn confirm_release(origin: OriginFor<T>, nonce: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_owner(&who)?;
+
+			let release = <PendingReleases<T, I>>::take(nonce).ok_or(Error::<T, I>::UnknownRelease)?;
+			<PendingOutbound<T, I>>::mutate(|pending| *pending = pending.saturating_sub(release.amount));
+			Self::deposit_event(Event::ReleaseConfirmed { nonce });
+			Ok(())
+		}
+
+		/// Queue `migrations` to be executed in `on_idle`, as far as the remaining weight of each
+		/// block allows.
+		///
+		/// Dispatched in the operational class. The origin must be the migration owner, whose
+		/// successful calls are free.
+		#[pallet::call_index(24)]
+		#[pallet::weight((T::WeightInfo::enqueue_migrations(migrations.len() as u32), DispatchClass::Operational))]
+		pub fn enqueue_migrations(origin: OriginFor<T>, migrations: MigrationBatch<T, I>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_owner(&who)?;
+
+			let head = <MigrationQueueHead<T, I>>::get();
+			let first = <MigrationQueueTail<T, I>>::get();
+			let count = migrations.len() as u32;
+			ensure!(first - head + count <= T::MaxQueuedMigrations::get(), Error::<T, I>::MigrationQueueFull);
+			let tail = first.checked_add(count).ok_or(ArithmeticError::Overflow)?;
+
+			for (index, migration) in (first..tail).zip(migrations) {
+				<MigrationQueue<T, I>>::insert(index, migration);
+			}
+			<MigrationQueueTail<T, I>>::put(tail);
+			Self::deposit_event(Event::MigrationsQueued { first, count });
+			Ok(Pays::No.into())
+		}
+
+		/// Create or update the allocation category `name`, migrated from its own sub-vault up to
+		/// `cap` in total.
+		///
+		/// A new `vesting` also applies to the amounts already migrated from the category, and
+		/// removing it releases them.
+		#[pallet::call_index(25)]
+		#[pallet::weight(T::WeightInfo::set_category())]
+		pub fn set_category(origin: OriginFor<T>, name: CategoryName<T, I>, cap: BalanceOf<T, I>, vesting: Option<CategoryVesting<T::BlockNumber>>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let category = match <Categories<T, I>>::get(&name) {
+				Some(category) => {
+					ensure!(cap >= category.migrated, Error::<T, I>::CategoryCapBelowMigrated);
+					AllocationCategory { cap, vesting, ..category }
+				},
+				None => {
+					ensure!(<Categories<T, I>>::count() < T::MaxCategories::get(), Error::<T, I>::TooManyCategories);
+					let vault = Self::category_vault(&name);
+					<CategoryVaults<T, I>>::insert(&vault, ());
+					AllocationCategory { vault, cap, migrated: Zero::zero(), vesting }
+				},
+			};
+			let vault = category.vault.clone();
+			<Categories<T, I>>::insert(&name, category);
+			Self::deposit_event(Event::CategorySet { name, vault, cap, vesting });
+			Ok(())
+		}
+
+		/// Bind `destination` as the only account migrations for `for_account` may credit.
+		///
+		/// `signature` is the signature of [`Pallet::destination_payload`] by the holder of
+		/// `for_account`. Anyone can submit it.
+		#[pallet::call_index(26)]
+		#[pallet::weight(T::WeightInfo::register_destination())]
+		pub fn register_destination(origin: OriginFor<T>, for_account: T::SourceAccountId, destination: T::AccountId, signature: T::SourceSignature) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let payload = Self::destination_payload(&for_account, &destination);
+			ensure!(signature.verify(&payload, &for_account), Error::<T, I>::InvalidSourceSignature);
+
+			<DestinationBindingNonces<T, I>>::mutate(&for_account, |nonce| *nonce = nonce.saturating_add(1));
+			<BoundDestinations<T, I>>::insert(&for_account, &destination);
+			Self::deposit_event(Event::DestinationRegistered { for_account, destination });
+			Ok(())
+		}
+
+		/// Dispute the migrations credited for `for_account`, with the hash of the evidence kept
+		/// off chain.
+		///
+		/// `signature` is the signature of [`Pallet::dispute_payload`] by the holder of
+		/// `for_account`, so only holders can dispute their migrations. Reserves `DisputeBond`
+		/// from the caller and freezes the amounts credited for `for_account` until the admin
+		/// origin resolves the dispute. No further migrations for `for_account` are executed
+		/// meanwhile.
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::WeightInfo::dispute(T::MaxDisputedCredits::get()))]
+		pub fn dispute(origin: OriginFor<T>, for_account: T::SourceAccountId, evidence_hash: T::Hash, signature: T::SourceSignature) -> DispatchResultWithPostInfo {
+			let disputer = ensure_signed(origin)?;
+			ensure!(!<OpenDisputes<T, I>>::contains_key(&for_account), Error::<T, I>::DisputeAlreadyOpen);
+			let payload = Self::dispute_payload(&for_account, &evidence_hash);
+			ensure!(signature.verify(&payload, &for_account), Error::<T, I>::InvalidSourceSignature);
+
+			let credits: Vec<_> = <Credits<T, I>>::iter_prefix(&for_account)
+				.take(T::MaxDisputedCredits::get() as usize + 1)
+				.collect();
+			ensure!(!credits.is_empty(), Error::<T, I>::NothingCredited);
+			let credits: DisputedCreditsOf<T, I> = credits.try_into().map_err(|_| Error::<T, I>::TooManyCredits)?;
+
+			let id = <NextDisputeId<T, I>>::get();
+			<NextDisputeId<T, I>>::put(id.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+			let bond = T::DisputeBond::get();
+			T::LocalToken::reserve(&disputer, bond)?;
+
+			for (who, amount) in credits.iter() {
+				<DisputedBalances<T, I>>::mutate(who, |disputed| *disputed = disputed.saturating_add(*amount));
+			}
+			let count = credits.len() as u32;
+			<DisputeNonces<T, I>>::mutate(&for_account, |nonce| *nonce = nonce.saturating_add(1));
+			<OpenDisputes<T, I>>::insert(&for_account, id);
+			<Disputes<T, I>>::insert(id, Dispute {
+				for_account: for_account.clone(),
+				disputer: disputer.clone(),
+				bond,
+				evidence_hash,
+				opened: <frame_system::Pallet<T>>::block_number(),
+				credits,
+				status: DisputeStatus::Open,
+			});
+			Self::deposit_event(Event::DisputeOpened { id, for_account, disputer, evidence_hash });
+			Ok(Some(T::WeightInfo::dispute(count)).into())
+		}
+
+		/// Resolve the open dispute `id` by moving the disputed credits to `redirect_to`, or by
+		/// releasing them with `None`.
+		///
+		/// Each credited account gives up to its disputed amount, as far as its transferable
+		/// balance allows. The rest stays credited to it and is reported as the shortfall. Amounts
+		/// migrated from categories move along with their vesting. Credits cannot be redirected
+		/// while the transfer lock is active.
+		#[pallet::call_index(28)]
+		#[pallet::weight(T::WeightInfo::resolve_dispute(T::MaxDisputedCredits::get()))]
+		pub fn resolve_dispute(origin: OriginFor<T>, id: u32, redirect_to: Option<T::AccountId>) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let mut dispute = <Disputes<T, I>>::get(id).ok_or(Error::<T, I>::UnknownDispute)?;
+			ensure!(matches!(dispute.status, DisputeStatus::Open), Error::<T, I>::DisputeNotOpen);
+
+			for (who, amount) in dispute.credits.iter() {
+				<DisputedBalances<T, I>>::mutate_exists(who, |disputed| {
+					let left = disputed.unwrap_or_default().saturating_sub(*amount);
+					*disputed = (!left.is_zero()).then_some(left);
+				});
+			}
+
+			let at = <frame_system::Pallet<T>>::block_number();
+			dispute.status = match redirect_to {
+				Some(to) => {
+					ensure!(!Self::transfers_locked(), Error::<T, I>::TransfersLocked);
+					let asset_id = <TokenId<T, I>>::get().ok_or(Error::<T, I>::AssetIdNoValue)?;
+					let mut moved = BalanceOf::<T, I>::zero();
+					let mut shortfall = BalanceOf::<T, I>::zero();
+					for (who, disputed) in dispute.credits.iter() {
+						// Vesting moves along with the tokens, which also makes the vesting part
+						// of them transferable here.
+						let shifted = Self::shift_category_amounts(who, &to, *disputed);
+						let balance = <T as ConfigHelper<I>>::asset_to_currency(T::Assets::reducible_balance(asset_id, who, false));
+						let amount = (*disputed).min(balance);
+						if !amount.is_zero() {
+							T::Assets::transfer(asset_id, who, &to, <T as ConfigHelper<I>>::currency_to_asset(amount), false)?;
+						}
+						// Vesting of what could not be moved stays with `who`.
+						let mut kept = shifted.iter().fold(BalanceOf::<T, I>::zero(), |total, (_, shifted)| total.saturating_add(*shifted)).saturating_sub(amount);
+						for (name, shifted) in shifted.iter().rev() {
+							let back = (*shifted).min(kept);
+							Self::shift_category_amount(&to, who, name, back);
+							kept = kept.saturating_sub(back);
+						}
+						<Credits<T, I>>::mutate_exists(&dispute.for_account, who, |credited| {
+							let left = credited.unwrap_or_default().saturating_sub(amount);
+							*credited = (!left.is_zero()).then_some(left);
+						});
+						moved = moved.saturating_add(amount);
+						shortfall = shortfall.saturating_add(disputed.saturating_sub(amount));
+					}
+					<Credits<T, I>>::mutate(&dispute.for_account, &to, |credited| *credited = credited.saturating_add(moved));
+					<MigrationRecipients<T, I>>::insert(&to, ());
+					T::LocalToken::unreserve(&dispute.disputer, dispute.bond);
+					Self::deposit_event(Event::DisputeRedirected { id, to: to.clone(), amount: moved, shortfall });
+					DisputeStatus::Redirected { at, to, amount: moved, shortfall }
+				},
+				None => {
+					let (slashed, _) = T::LocalToken::slash_reserved(&dispute.disputer, dispute.bond);
+					T::DisputeSlash::on_unbalanced(slashed);
+					Self::deposit_event(Event::DisputeReleased { id });
+					DisputeStatus::Released { at }
+				},
+			};
+
+			let count = dispute.credits.len() as u32;
+			<OpenDisputes<T, I>>::remove(&dispute.for_account);
+			<Disputes<T, I>>::insert(id, dispute);
+			Ok(Some(T::WeightInfo::resolve_dispute(count)).into())
+		}
+	}
+
+	/// Fees of a migration recipient paid by a sponsor.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Sponsorship<AccountId, Balance, Pallets> {
+		pub sponsor: AccountId,
+		/// Fees the sponsor still pays.
+		pub remaining: Balance,
+		/// Indices of the pallets whose calls are sponsored.
+		pub pallets: Pallets,
+	}
+
+	/// Release on the source chain of tokens moved back with `migrate_back`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct OutboundRelease<AccountId, SourceAccountId, Balance> {
+		pub who: AccountId,
+		/// Recipient on the source chain.
+		pub to: SourceAccountId,
+		pub amount: Balance,
+	}
+
+	/// Migration waiting in the queue drained in `on_idle`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct QueuedMigration<SourceAccountId, AccountId, Balance, SourceRef, Category> {
+		pub for_account: SourceAccountId,
+		pub account_to_credit: AccountId,
+		pub amount: Balance,
+		pub source_ref: Option<SourceRef>,
+		/// Allocation category to migrate from, see [`Pallet::migrate`].
+		pub category: Option<Category>,
+	}
+
+	/// Progress of the migration queue.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct MigrationQueueProgress {
+		/// Queued migrations executed so far, including failed ones.
+		pub processed: u32,
+		/// Queued migrations waiting to be executed.
+		pub pending: u32,
+	}
+
+	/// Totals of an allocation category as reported by [`Pallet::category_totals`].
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct CategoryTotals<AccountId, Balance> {
+		pub name: Vec<u8>,
+		pub vault: AccountId,
+		pub cap: Balance,
+		pub migrated: Balance,
+		/// Balance left in the sub-vault of the category.
+		pub vault_balance: Balance,
+	}
+
+	/// A single migration as submitted to [`Pallet::dry_run_migrate`].
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct MigrationRequest<SourceAccountId, AccountId, Balance> {
+		pub for_account: SourceAccountId,
+		pub account_to_credit: AccountId,
+		pub amount: Balance,
+		pub source_ref: Option<Vec<u8>>,
+		/// Name of the allocation category to migrate from, see [`Pallet::migrate`].
+		pub category: Option<Vec<u8>>,
+	}
+
+	/// Per-item result of [`Pallet::dry_run_migrate`].
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub enum MigrationDryRunOutcome<Balance> {
+		Success {
+			vault_balance_remained: Balance,
+			account_balance_after: Balance,
+		},
+		Failed(DispatchError),
+	}
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Transfer `amount` from the vault to `account_to_credit` on behalf of `who`.
+		///
+		/// Returns the vault and account balances after the transfer.
+		pub(crate) fn do_migrate(who: &T::AccountId, for_account: T::SourceAccountId, account_to_credit: T::AccountId, amount: BalanceOf<T, I>, source_ref: Option<SourceRef<T, I>>) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
+			Self::do_migrate_from(who, for_account, account_to_credit, amount, source_ref, None)
+		}
+
+		/// Transfer `amount` from the vault, or the sub-vault of `category`, to
+		/// `account_to_credit` on behalf of `who`.
+		///
+		/// Returns the vault and account balances after the transfer.
+		pub(crate) fn do_migrate_from(who: &T::AccountId, for_account: T::SourceAccountId, account_to_credit: T::AccountId, amount: BalanceOf<T, I>, source_ref: Option<SourceRef<T, I>>, category: Option<CategoryName<T, I>>) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
+			ensure!( <MigrationVaultAccount<T, I>>::exists(),
+				Error::<T, I>::MigrationVaultAccountNoValue
+			);
+
+			ensure!(<MigrationOwner<T, I>>::exists(),
+				Error::<T, I>::MigrationOwnerNoValue
+			);
+
+			ensure!(<TokenId<T, I>>::exists(),
+				Error::<T, I>::AssetIdNoValue
 			);
 
-			let owner = <MigrationOwner<T>>::get().unwrap();
-			let migration_account = <MigrationVaultAccount<T>>::get().unwrap();
-			let asset_id = <TokenId<T>>::get().unwrap();
-			let migration_amount = T::currency_to_asset(amount);
+			let owner = <MigrationOwner<T, I>>::get().unwrap();
+			let mut migration_account = <MigrationVaultAccount<T, I>>::get().unwrap();
+			let asset_id = <TokenId<T, I>>::get().unwrap();
+			let migration_amount = <T as ConfigHelper<I>>::currency_to_asset(amount);
 
 			#[cfg(feature = "std")]
 			{
@@ -205,14 +1704,50 @@ pub mod pallet {
 			}
 
 			ensure!(
-				owner == who,
-				Error::<T>::Unauthorised
+				&owner == who,
+				Error::<T, I>::Unauthorised
 			);
+
+			if let Some(destination) = <BoundDestinations<T, I>>::get(&for_account) {
+				ensure!(destination == account_to_credit, Error::<T, I>::DestinationMismatch);
+			}
+
+			ensure!(!<OpenDisputes<T, I>>::contains_key(&for_account), Error::<T, I>::SourceAccountDisputed);
+
+			if let Some(source_ref) = &source_ref {
+				ensure!(
+					!<ProcessedSourceRefs<T, I>>::contains_key(source_ref),
+					Error::<T, I>::SourceRefAlreadyUsed
+				);
+				<ProcessedSourceRefs<T, I>>::insert(source_ref, <frame_system::Pallet<T>>::block_number());
+			}
+
+			if <AllocationCommitment<T, I>>::exists() {
+				ensure!(Self::allocations_sealed(), Error::<T, I>::AllocationsNotSealed);
+				let allocated = <Allocations<T, I>>::get(&for_account).ok_or(Error::<T, I>::NoAllocation)?;
+				let migrated = <MigratedAmounts<T, I>>::get(&for_account)
+					.checked_add(&amount)
+					.ok_or(ArithmeticError::Overflow)?;
+				ensure!(migrated <= allocated, Error::<T, I>::AllocationExceeded);
+				<MigratedAmounts<T, I>>::insert(&for_account, migrated);
+			}
+
+			if let Some(name) = &category {
+				let mut category = <Categories<T, I>>::get(name).ok_or(Error::<T, I>::UnknownCategory)?;
+				category.migrated = category.migrated.checked_add(&amount).ok_or(ArithmeticError::Overflow)?;
+				ensure!(category.migrated <= category.cap, Error::<T, I>::CategoryCapExceeded);
+				<CategoryAmounts<T, I>>::mutate(&account_to_credit, name, |migrated| *migrated = migrated.saturating_add(amount));
+				migration_account = category.vault.clone();
+				<Categories<T, I>>::insert(name, category);
+			}
 			
 			T::Assets::transfer(asset_id, &migration_account, &account_to_credit, migration_amount, true)?;
+			<MigrationRecipients<T, I>>::insert(&account_to_credit, ());
+			<Credits<T, I>>::mutate(&for_account, &account_to_credit, |credited| *credited = credited.saturating_add(amount));
+			T::OnMigrated::on_migrated(&for_account, &account_to_credit, amount, asset_id);
 
-			let vault_balance = T::Assets::balance(asset_id, &migration_account);
-			let account_balance = T::Assets::balance(asset_id, &account_to_credit);
+			let vault_balance = <T as ConfigHelper<I>>::asset_to_currency(T::Assets::balance(asset_id, &migration_account));
+			let account_balance = <T as ConfigHelper<I>>::asset_to_currency(T::Assets::balance(asset_id, &account_to_credit));
 
 			#[cfg(feature = "std")]
 			{
@@ -225,10 +1760,293 @@ pub mod pallet {
 				from_vault: migration_account, 
 				for_account: for_account, 
 				to_account: account_to_credit,
-				vault_balance_remained: T::asset_to_currency(vault_balance),
-				account_balance_after: T::asset_to_currency(account_balance),
+				vault_balance_remained: vault_balance,
+				account_balance_after: account_balance,
+				source_ref,
+			});
+			Ok((vault_balance, account_balance))
+		}
+
+		/// Sponsor paying `fee` for a call of `who` to the pallet at `pallet_index`, if any.
+		pub fn sponsor_for(who: &T::AccountId, pallet_index: u8, fee: BalanceOf<T, I>) -> Option<T::AccountId> {
+			let sponsorship = <Sponsorships<T, I>>::get(who)?;
+			let covered = <Sponsors<T, I>>::contains_key(&sponsorship.sponsor)
+				&& sponsorship.pallets.contains(&pallet_index)
+				&& sponsorship.remaining >= fee;
+			covered.then_some(sponsorship.sponsor)
+		}
+
+		/// Take `fee` paid by the sponsor of `who` from the sponsorship.
+		pub(crate) fn charge_sponsorship(who: &T::AccountId, fee: BalanceOf<T, I>) {
+			<Sponsorships<T, I>>::mutate(who, |sponsorship| {
+				if let Some(sponsorship) = sponsorship {
+					sponsorship.remaining = sponsorship.remaining.saturating_sub(fee);
+				}
+			});
+		}
+
+		/// Return `refund` of a fee paid by the sponsor of `who` to the sponsorship.
+		pub(crate) fn refund_sponsorship(who: &T::AccountId, refund: BalanceOf<T, I>) {
+			<Sponsorships<T, I>>::mutate(who, |sponsorship| {
+				if let Some(sponsorship) = sponsorship {
+					sponsorship.remaining = sponsorship.remaining.saturating_add(refund);
+				}
 			});
+		}
+
+		/// Trust the state root of the finalized source-chain `header` for burn claims.
+		fn finalize_source_header(header: &SourceHeader) {
+			let hash = header.hash();
+			<BestFinalizedSourceHeader<T, I>>::put((header.number, hash));
+			<FinalizedSourceRoots<T, I>>::insert(header.number, header.state_root);
+			Self::deposit_event(Event::SourceHeaderFinalized { number: header.number, hash });
+		}
+
+		/// Ensure `who` is the migration owner.
+		pub(crate) fn ensure_owner(who: &T::AccountId) -> DispatchResult {
+			let owner = <MigrationOwner<T, I>>::get().ok_or(Error::<T, I>::MigrationOwnerNoValue)?;
+			ensure!(&owner == who, Error::<T, I>::Unauthorised);
 			Ok(())
 		}
+
+		fn next_allocation_accumulator(accumulator: T::Hash, source: &T::SourceAccountId, amount: &BalanceOf<T, I>) -> T::Hash {
+			T::Hashing::hash_of(&(accumulator, source, amount))
+		}
+
+		/// Commitment to an allocation table, to be passed to `commit_allocations`.
+		///
+		/// Starting from the default hash, each entry is folded in as
+		/// `hash(SCALE(accumulator, source, amount))` using the runtime hashing algorithm.
+		pub fn allocation_commitment(entries: &[(T::SourceAccountId, BalanceOf<T, I>)]) -> T::Hash {
+			entries.iter().fold(T::Hash::default(), |accumulator, (source, amount)| {
+				Self::next_allocation_accumulator(accumulator, source, amount)
+			})
+		}
+
+		/// The pallet account, which owns the migrated asset. No key exists for it.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Sub-vault of the allocation category `name`. No key exists for it.
+		pub fn category_vault(name: &CategoryName<T, I>) -> T::AccountId {
+			T::PalletId::get().into_sub_account_truncating((b"category", name))
+		}
+
+		/// Move up to `amount` of the amounts migrated to `from` from categories to `to`, and
+		/// return how much was moved from each category.
+		fn shift_category_amounts(from: &T::AccountId, to: &T::AccountId, amount: BalanceOf<T, I>) -> Vec<(CategoryName<T, I>, BalanceOf<T, I>)> {
+			let mut left = amount;
+			let mut shifted = Vec::new();
+			for (name, migrated) in <CategoryAmounts<T, I>>::iter_prefix(from).collect::<Vec<_>>() {
+				if left.is_zero() {
+					break
+				}
+				let moved = migrated.min(left);
+				Self::shift_category_amount(from, to, &name, moved);
+				left = left.saturating_sub(moved);
+				shifted.push((name, moved));
+			}
+			shifted
+		}
+
+		fn shift_category_amount(from: &T::AccountId, to: &T::AccountId, name: &CategoryName<T, I>, amount: BalanceOf<T, I>) {
+			if amount.is_zero() {
+				return
+			}
+			<CategoryAmounts<T, I>>::mutate_exists(from, name, |migrated| {
+				let left = migrated.unwrap_or_default().saturating_sub(amount);
+				*migrated = (!left.is_zero()).then_some(left);
+			});
+			<CategoryAmounts<T, I>>::mutate(to, name, |migrated| *migrated = migrated.saturating_add(amount));
+		}
+
+		/// Amount migrated to `who` from categories with vesting that is not transferable yet.
+		pub fn vesting_locked(who: &T::AccountId) -> BalanceOf<T, I> {
+			let now = <frame_system::Pallet<T>>::block_number();
+			<CategoryAmounts<T, I>>::iter_prefix(who).fold(Zero::zero(), |locked: BalanceOf<T, I>, (name, amount)| {
+				let vesting = match <Categories<T, I>>::get(&name).and_then(|category| category.vesting) {
+					Some(vesting) => vesting,
+					None => return locked,
+				};
+				let end = vesting.start.saturating_add(vesting.duration);
+				let still_locked = if now <= vesting.start {
+					amount
+				} else if now >= end {
+					Zero::zero()
+				} else {
+					let remaining: u32 = (end - now).saturated_into();
+					Perbill::from_rational(remaining, vesting.duration.saturated_into::<u32>()).mul_ceil(amount)
+				};
+				locked.saturating_add(still_locked)
+			})
+		}
+
+		/// Cap, migrated total and remaining sub-vault balance of every allocation category.
+		pub fn category_totals() -> Vec<CategoryTotals<T::AccountId, BalanceOf<T, I>>> {
+			let asset_id = <TokenId<T, I>>::get();
+			let mut totals = <Categories<T, I>>::iter()
+				.map(|(name, category)| CategoryTotals {
+					vault_balance: asset_id
+						.map(|asset_id| <T as ConfigHelper<I>>::asset_to_currency(T::Assets::balance(asset_id, &category.vault)))
+						.unwrap_or_else(Zero::zero),
+					name: name.into(),
+					vault: category.vault,
+					cap: category.cap,
+					migrated: category.migrated,
+				})
+				.collect::<Vec<_>>();
+			totals.sort_by(|a, b| a.name.cmp(&b.name));
+			totals
+		}
+
+		/// Whether transfers of the migrated asset out of accounts other than the vault are locked.
+		pub fn transfers_locked() -> bool {
+			matches!(<TransferLockUntil<T, I>>::get(), Some(until) if <frame_system::Pallet<T>>::block_number() < until)
+		}
+
+		/// Releases on the source chain that are not confirmed yet, by nonce.
+		pub fn pending_releases() -> Vec<(u64, OutboundRelease<T::AccountId, T::SourceAccountId, BalanceOf<T, I>>)> {
+			let mut releases = <PendingReleases<T, I>>::iter().collect::<Vec<_>>();
+			releases.sort_by_key(|(nonce, _)| *nonce);
+			releases
+		}
+
+		/// Execute queued migrations in order as long as `budget` allows, as the migration owner.
+		///
+		/// Failed migrations are rolled back and kept in [`FailedMigrations`]. Returns the weight
+		/// used.
+		pub(crate) fn process_migration_queue(budget: Weight) -> Weight {
+			let mut used = T::DbWeight::get().reads(3);
+			if !budget.all_gte(used) {
+				return Weight::zero()
+			}
+			let mut head = <MigrationQueueHead<T, I>>::get();
+			let tail = <MigrationQueueTail<T, I>>::get();
+			let owner = match <MigrationOwner<T, I>>::get() {
+				Some(owner) if head < tail => owner,
+				_ => return used,
+			};
+
+			let start = head;
+			let item = T::WeightInfo::process_queued_migration().saturating_add(T::OnMigrated::weight());
+			while head < tail && budget.all_gte(used.saturating_add(item)) {
+				if let Some(migration) = <MigrationQueue<T, I>>::take(head) {
+					let result = with_transaction(|| {
+						let m = migration.clone();
+						match Self::do_migrate_from(&owner, m.for_account, m.account_to_credit, m.amount, m.source_ref, m.category) {
+							Ok(_) => TransactionOutcome::Commit(Ok(())),
+							Err(e) => TransactionOutcome::Rollback(Err(e)),
+						}
+					});
+					if let Err(error) = result {
+						<FailedMigrations<T, I>>::insert(head, migration);
+						Self::deposit_event(Event::QueuedMigrationFailed { index: head, error });
+					}
+				}
+				head += 1;
+				used = used.saturating_add(item);
+			}
+			if head != start {
+				<MigrationQueueHead<T, I>>::put(head);
+			}
+			used
+		}
+
+		/// Progress of the migration queue.
+		pub fn migration_queue_progress() -> MigrationQueueProgress {
+			let head = <MigrationQueueHead<T, I>>::get();
+			MigrationQueueProgress { processed: head, pending: <MigrationQueueTail<T, I>>::get() - head }
+		}
+
+		/// Queued migrations that failed, by queue index.
+		pub fn failed_migrations() -> Vec<(u32, MigrationRequest<T::SourceAccountId, T::AccountId, BalanceOf<T, I>>)> {
+			let mut failed = <FailedMigrations<T, I>>::iter()
+				.map(|(index, m)| (index, MigrationRequest {
+					for_account: m.for_account,
+					account_to_credit: m.account_to_credit,
+					amount: m.amount,
+					source_ref: m.source_ref.map(Into::into),
+					category: m.category.map(Into::into),
+				}))
+				.collect::<Vec<_>>();
+			failed.sort_by_key(|(index, _)| *index);
+			failed
+		}
+
+		/// Evaluate `migrations` in order against the current state as if `who` submitted them.
+		///
+		/// Each item sees the effects of the successful items before it, but every change is
+		/// rolled back before returning, so nothing is written to storage.
+		pub fn dry_run_migrate(
+			who: T::AccountId,
+			migrations: Vec<MigrationRequest<T::SourceAccountId, T::AccountId, BalanceOf<T, I>>>,
+		) -> Vec<MigrationDryRunOutcome<BalanceOf<T, I>>> {
+			let outcomes = with_transaction(|| {
+				let outcomes = migrations
+					.into_iter()
+					.map(|m| {
+						let result = with_transaction(|| {
+							let source_ref = match m.source_ref.map(SourceRef::<T, I>::try_from).transpose() {
+								Ok(source_ref) => source_ref,
+								Err(_) => return TransactionOutcome::Rollback(Err(Error::<T, I>::SourceRefTooLong.into())),
+							};
+							// No category can have a name longer than the bound.
+							let category = match m.category.map(CategoryName::<T, I>::try_from).transpose() {
+								Ok(category) => category,
+								Err(_) => return TransactionOutcome::Rollback(Err(Error::<T, I>::UnknownCategory.into())),
+							};
+							match Self::do_migrate_from(&who, m.for_account, m.account_to_credit, m.amount, source_ref, category) {
+								Ok(balances) => TransactionOutcome::Commit(Ok(balances)),
+								Err(e) => TransactionOutcome::Rollback(Err(e)),
+							}
+						});
+						match result {
+							Ok((vault_balance_remained, account_balance_after)) =>
+								MigrationDryRunOutcome::Success { vault_balance_remained, account_balance_after },
+							Err(e) => MigrationDryRunOutcome::Failed(e),
+						}
+					})
+					.collect::<Vec<_>>();
+				TransactionOutcome::Rollback(Ok::<_, DispatchError>(outcomes))
+			});
+			outcomes.unwrap_or_default()
+		}
+	}
+
+	/// Use as the `pallet_assets` `Freezer` to enforce the transfer lock, the vesting of
+	/// allocation categories and open disputes on the migrated asset.
+	///
+	/// While the lock is active the whole balance of every account but the vaults is frozen, so
+	/// migrations out of the vaults keep working. Afterwards the amounts still vesting and the
+	/// disputed credits are frozen.
+	impl<T: Config<I>, I: 'static> FrozenBalance<T::TokenId, T::AccountId, T::AssetBalance> for Pallet<T, I> {
+		fn frozen_balance(asset: T::TokenId, who: &T::AccountId) -> Option<T::AssetBalance> {
+			if <TokenId<T, I>>::get() != Some(asset) {
+				return None
+			}
+			if <MigrationVaultAccount<T, I>>::get().as_ref() == Some(who) || <CategoryVaults<T, I>>::contains_key(who) {
+				return None
+			}
+			if Self::transfers_locked() {
+				return Some(T::Assets::balance(asset, who))
+			}
+			let frozen = Self::vesting_locked(who).saturating_add(<DisputedBalances<T, I>>::get(who));
+			(!frozen.is_zero()).then(|| <T as ConfigHelper<I>>::currency_to_asset(frozen))
+		}
+
+		fn died(_asset: T::TokenId, _who: &T::AccountId) {}
+	}
+
+	/// Converts fees in the local currency to the migrated asset at [`FeeRate`], e.g. for
+	/// `pallet_asset_tx_payment`. Other assets can't be used to pay fees.
+	impl<T: Config<I>, I: 'static> BalanceConversion<BalanceOf<T, I>, T::TokenId, T::AssetBalance> for Pallet<T, I> {
+		type Error = Error<T, I>;
+
+		fn to_asset_balance(balance: BalanceOf<T, I>, asset_id: T::TokenId) -> Result<T::AssetBalance, Self::Error> {
+			ensure!(<TokenId<T, I>>::get() == Some(asset_id), Error::<T, I>::NotMigratedAsset);
+			let rate = <FeeRate<T, I>>::get().ok_or(Error::<T, I>::FeeRateNotSet)?;
+			Ok(<T as ConfigHelper<I>>::currency_to_asset(rate.saturating_mul_int(balance)))
+		}
 	}
 }
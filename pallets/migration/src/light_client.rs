@@ -0,0 +1,191 @@
+//! GRANDPA light client of a Substrate source chain.
+//!
+//! Starting from a trusted header and authority set, a relayer submits source-chain headers with
+//! GRANDPA justifications. The state roots of finalized headers are kept in
+//! `FinalizedSourceRoots`, apart from the snapshot roots set by governance, and only burn events
+//! can be proven against them. Every header that changes the authority set has to be submitted,
+//! in order.
+
+use crate::pallet::*;
+use codec::{Decode, Encode};
+use finality_grandpa::{Commit, Message};
+use frame_support::{ensure, Twox128, StorageHasher};
+use sp_core::H256;
+use sp_finality_grandpa::{
+	AuthorityId, AuthorityList, AuthoritySignature, ConsensusLog, SetId, GRANDPA_ENGINE_ID,
+};
+use sp_runtime::{
+	generic::{self, OpaqueDigestItemId},
+	traits::{BlakeTwo256, Header as HeaderT},
+	RuntimeAppPublic, RuntimeDebug,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// Header of the source chain.
+pub type SourceHeader = generic::Header<u32, BlakeTwo256>;
+
+/// GRANDPA justification of a source-chain header, as stored with the finalized block.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct GrandpaJustification {
+	pub round: u64,
+	pub commit: Commit<H256, u32, AuthoritySignature, AuthorityId>,
+	pub votes_ancestries: Vec<SourceHeader>,
+}
+
+/// Runtime event of the source chain.
+pub type SourceEventOf<T, I = ()> = <<T as Config<I>>::SourceBurns as SourceBurn<
+	<T as Config<I>>::SourceAccountId,
+	<T as frame_system::Config>::AccountId,
+>>::Event;
+
+/// Burn of tokens on the source chain that names the account to credit on this chain.
+pub trait SourceBurn<SourceAccountId, AccountId> {
+	/// Runtime event type of the source chain.
+	type Event: Decode;
+
+	/// Holder, burned amount and account to credit, if `event` is a burn.
+	fn burn(event: &Self::Event) -> Option<(SourceAccountId, u128, AccountId)>;
+
+	/// Encoded burn event, to benchmark claiming burns.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn encode_burn(_for_account: SourceAccountId, _amount: u128, _account_to_credit: AccountId) -> Option<Vec<u8>> {
+		None
+	}
+}
+
+/// No burns can be proven.
+impl<SourceAccountId, AccountId> SourceBurn<SourceAccountId, AccountId> for () {
+	type Event = ();
+
+	fn burn(_event: &()) -> Option<(SourceAccountId, u128, AccountId)> {
+		None
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Check that `justification` finalizes `header` with the votes of more than two thirds of
+	/// the weight of `authorities` in set `set_id`.
+	pub(crate) fn verify_justification(
+		header: &SourceHeader,
+		justification: &GrandpaJustification,
+		set_id: SetId,
+		authorities: &[(AuthorityId, u64)],
+	) -> Result<(), Error<T, I>> {
+		let commit = &justification.commit;
+		ensure!(
+			commit.target_hash == header.hash() && commit.target_number == header.number,
+			Error::<T, I>::InvalidJustification
+		);
+		ensure!(
+			justification.votes_ancestries.len() <= T::MaxSourceAuthorities::get() as usize,
+			Error::<T, I>::InvalidJustification
+		);
+
+		let parents = justification
+			.votes_ancestries
+			.iter()
+			.map(|ancestor| (ancestor.hash(), ancestor.parent_hash))
+			.collect::<BTreeMap<_, _>>();
+		let mut voters = Vec::with_capacity(commit.precommits.len());
+		let mut weight = 0u64;
+		for signed in &commit.precommits {
+			let voter_weight = authorities
+				.iter()
+				.find(|(id, _)| id == &signed.id)
+				.map(|(_, weight)| *weight)
+				.ok_or(Error::<T, I>::InvalidJustification)?;
+			ensure!(!voters.contains(&&signed.id), Error::<T, I>::InvalidJustification);
+
+			// The vote must be for the target or one of its descendants.
+			let mut block = signed.precommit.target_hash;
+			let mut hops = 0;
+			while block != commit.target_hash {
+				ensure!(hops < parents.len(), Error::<T, I>::InvalidJustification);
+				block = *parents.get(&block).ok_or(Error::<T, I>::InvalidJustification)?;
+				hops += 1;
+			}
+
+			let payload = (Message::Precommit(signed.precommit.clone()), justification.round, set_id).encode();
+			ensure!(signed.id.verify(&payload, &signed.signature), Error::<T, I>::InvalidJustification);
+
+			voters.push(&signed.id);
+			weight = weight.saturating_add(voter_weight);
+		}
+
+		let total = authorities.iter().fold(0u64, |total, (_, weight)| total.saturating_add(*weight));
+		let threshold = total - total.saturating_sub(1) / 3;
+		ensure!(weight >= threshold, Error::<T, I>::InvalidJustification);
+		Ok(())
+	}
+
+	/// Next authority set scheduled by `header`, if any. Only changes enacted by the header
+	/// itself are supported, as scheduled by `pallet_grandpa` without a delay.
+	pub(crate) fn scheduled_authority_change(header: &SourceHeader) -> Result<Option<AuthorityList>, Error<T, I>> {
+		let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
+		let mut next = None;
+		for log in header.digest().logs().iter().filter_map(|log| log.try_to::<ConsensusLog<u32>>(id)) {
+			match log {
+				ConsensusLog::ScheduledChange(change) if change.delay == 0 =>
+					next = Some(change.next_authorities),
+				ConsensusLog::ScheduledChange(_) | ConsensusLog::ForcedChange(..) =>
+					return Err(Error::<T, I>::UnsupportedAuthoritySetChange),
+				_ => {},
+			}
+		}
+		Ok(next)
+	}
+
+	/// Event at `index` of `System::Events` proven by `proof` against the source-chain state
+	/// `root`.
+	pub(crate) fn prove_source_event(
+		root: &H256,
+		index: u32,
+		proof: Vec<Vec<u8>>,
+	) -> Result<SourceEventOf<T, I>, Error<T, I>> {
+		let key = [Twox128::hash(b"System").to_vec(), Twox128::hash(b"Events").to_vec()].concat();
+		let events = Self::read_source_storage(root, &key, proof)?.ok_or(Error::<T, I>::NoSuchSourceEvent)?;
+		// `Vec<EventRecord { phase, event, topics }>`
+		let mut records = Vec::<(frame_system::Phase, SourceEventOf<T, I>, Vec<H256>)>::decode(&mut &events[..])
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)?;
+		ensure!((index as usize) < records.len(), Error::<T, I>::NoSuchSourceEvent);
+		Ok(records.swap_remove(index as usize).1)
+	}
+}
+
+/// Justification of `header` in `round` of set `set_id`, with a precommit for the header by each
+/// of `voters` signed by `sign`.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn build_justification(
+	header: &SourceHeader,
+	round: u64,
+	set_id: SetId,
+	voters: &[AuthorityId],
+	sign: impl Fn(&AuthorityId, &[u8]) -> AuthoritySignature,
+) -> GrandpaJustification {
+	use finality_grandpa::{Precommit, SignedPrecommit};
+
+	let precommit = Precommit { target_hash: header.hash(), target_number: header.number };
+	let payload = (Message::<H256, u32>::Precommit(precommit.clone()), round, set_id).encode();
+	let precommits = voters
+		.iter()
+		.map(|id| SignedPrecommit { precommit: precommit.clone(), signature: sign(id, &payload), id: id.clone() })
+		.collect();
+	GrandpaJustification {
+		round,
+		commit: Commit { target_hash: header.hash(), target_number: header.number, precommits },
+		votes_ancestries: Vec::new(),
+	}
+}
+
+/// Source-chain `System::Events` storage entry holding `events`, all in the same phase.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn source_events_entry(events: Vec<Vec<u8>>) -> (Vec<u8>, Vec<u8>) {
+	let key = [Twox128::hash(b"System").to_vec(), Twox128::hash(b"Events").to_vec()].concat();
+	let mut value = codec::Compact(events.len() as u32).encode();
+	for event in events {
+		value.extend(frame_system::Phase::ApplyExtrinsic(0).encode());
+		value.extend(event);
+		value.extend(Vec::<H256>::new().encode());
+	}
+	(key, value)
+}
@@ -1,11 +1,18 @@
 use crate::{self as pallet_migration};
 #[cfg(feature = "runtime-benchmarks")]
 use frame_benchmarking::{account, whitelisted_caller};
-use frame_support::traits::{ConstU16, ConstU64, ConstU128, ConstU32, AsEnsureOriginWithArg, Currency};
-use sp_core::H256;
+use frame_support::{
+	instances::Instance1,
+	parameter_types, PalletId,
+	traits::{ConstU16, ConstU64, ConstU128, ConstU32, ConstU8, AsEnsureOriginWithArg, Currency},
+	weights::IdentityFee,
+};
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256};
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, Identity, AccountIdLookup},
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, Identity, AccountIdLookup, Extrinsic as ExtrinsicT, Verify},
 	BuildStorage
 };
 use sp_core::sr25519::Public;
@@ -14,8 +21,11 @@ use frame_system::{EnsureSigned};
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestSuite>;
 type Block = frame_system::mocking::MockBlock<TestSuite>;
 type AccountId = crate::helpers::AccountId;
+type Signature = crate::helpers::Signature;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
 
 const TOKEN_ID: u32 = 1;
+pub const ETH_TOKEN_ID: u32 = 2;
 #[cfg(feature = "runtime-benchmarks")]
 const SEED: u32 = 0;
 #[cfg(feature = "runtime-benchmarks")]
@@ -30,8 +40,10 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system,
 		Migration: pallet_migration,
+		MigrationEth: pallet_migration::<Instance1>,
 		Balances: pallet_balances,
 		Assets:  pallet_assets,
+		TransactionPayment: pallet_transaction_payment,
 	}
 );
 
@@ -62,6 +74,141 @@ impl frame_system::Config for TestSuite {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+	pub const MigrationPalletId: PalletId = PalletId(*b"jur/migr");
+	pub const EthMigrationPalletId: PalletId = PalletId(*b"jur/meth");
+	pub const SystemAccountEntry: pallet_migration::SourceBalanceEntry = pallet_migration::SourceBalanceEntry::System;
+}
+
+/// Source account tagged with the chain it belongs to.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+pub enum SourceAccount {
+	Substrate([u8; 32]),
+	Ethereum(H160),
+}
+
+impl From<AccountId> for SourceAccount {
+	fn from(account: AccountId) -> Self {
+		Self::Substrate(account.into())
+	}
+}
+
+impl From<H160> for SourceAccount {
+	fn from(account: H160) -> Self {
+		Self::Ethereum(account)
+	}
+}
+
+/// Holders of Substrate accounts sign with their key, holders of Ethereum accounts with
+/// `personal_sign`.
+impl pallet_migration::SourceSignature<SourceAccount> for Signature {
+	fn verify(&self, message: &[u8], signer: &SourceAccount) -> bool {
+		match (signer, self) {
+			(SourceAccount::Substrate(account), _) => pallet_migration::SourceSignature::verify(self, message, account),
+			(SourceAccount::Ethereum(address), Signature::Ecdsa(signature)) => pallet_migration::SourceSignature::verify(signature, message, address),
+			_ => false,
+		}
+	}
+}
+
+thread_local! {
+	static MIGRATED: std::cell::RefCell<Vec<(SourceAccount, AccountId, Balance, u32)>> = Default::default();
+}
+
+/// Records every migration it is called for.
+pub struct MigrationLog;
+
+impl MigrationLog {
+	pub fn migrated() -> Vec<(SourceAccount, AccountId, Balance, u32)> {
+		MIGRATED.with(|migrated| migrated.borrow().clone())
+	}
+}
+
+impl pallet_migration::OnMigrated<SourceAccount, AccountId, Balance, u32> for MigrationLog {
+	fn on_migrated(for_account: &SourceAccount, who: &AccountId, amount: Balance, token_id: u32) {
+		MIGRATED.with(|migrated| migrated.borrow_mut().push((for_account.clone(), who.clone(), amount, token_id)));
+	}
+
+	fn weight() -> frame_support::weights::Weight {
+		frame_support::weights::Weight::from_ref_time(1_000_000)
+	}
+}
+
+/// ERC-20 token on Ethereum.
+pub struct Erc20Token;
+
+impl Erc20Token {
+	pub fn address() -> H160 {
+		H160::repeat_byte(0xe2)
+	}
+}
+
+impl<SourceAccountId: From<H160>> pallet_migration::Erc20Source<SourceAccountId> for Erc20Token {
+	fn token() -> Option<H160> {
+		Some(Self::address())
+	}
+
+	fn holder(address: H160) -> Option<SourceAccountId> {
+		Some(address.into())
+	}
+}
+
+/// Runtime event of the source chain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub enum SourceEvent {
+	Transferred { from: SourceAccount, to: SourceAccount, amount: u128 },
+	Burned { who: SourceAccount, amount: u128, to: AccountId },
+}
+
+pub struct SourceBurns;
+
+impl pallet_migration::SourceBurn<SourceAccount, AccountId> for SourceBurns {
+	type Event = SourceEvent;
+
+	fn burn(event: &SourceEvent) -> Option<(SourceAccount, u128, AccountId)> {
+		match event {
+			SourceEvent::Burned { who, amount, to } => Some((who.clone(), *amount, to.clone())),
+			_ => None,
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn encode_burn(who: SourceAccount, amount: u128, to: AccountId) -> Option<Vec<u8>> {
+		Some(SourceEvent::Burned { who, amount, to }.encode())
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MigrationBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_migration::BenchmarkHelper<codec::Compact<u32>, H160> for MigrationBenchmarkHelper {
+	fn create_asset_id_parameter(id: u32) -> codec::Compact<u32> {
+		id.into()
+	}
+
+	fn create_source_account(index: u32) -> H160 {
+		H160::from_low_u64_be(index as u64)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_migration::BenchmarkHelper<codec::Compact<u32>, SourceAccount> for MigrationBenchmarkHelper {
+	fn create_asset_id_parameter(id: u32) -> codec::Compact<u32> {
+		id.into()
+	}
+
+	// Alternate between both kinds of source accounts, so the allocation table mixes them.
+	fn create_source_account(index: u32) -> SourceAccount {
+		let hash = sp_io::hashing::blake2_256(&index.encode());
+		if index % 2 == 0 {
+			SourceAccount::Substrate(hash)
+		} else {
+			SourceAccount::Ethereum(H160::from_slice(&hash[12..]))
+		}
+	}
+}
+
 impl pallet_migration::Config for TestSuite {
 	type TokenId = u32;
 	type RuntimeEvent = RuntimeEvent;
@@ -73,13 +220,115 @@ impl pallet_migration::Config for TestSuite {
 	type MigrationVaultAccount = Public;
 	type MigrationOwner = Public;
 	type WeightInfo = ();
+	type SourceAccountId = SourceAccount;
+	type MaxSourceRefLength = ConstU32<64>;
+	type AuthorityId = crate::crypto::MigrationAuthId;
+	type OffchainRetryDelay = ConstU64<5>;
+	type MaxOffchainRetries = ConstU32<3>;
+	type MaxOffchainSubmissions = ConstU32<2>;
+	type MaxAllocationChunk = ConstU32<100>;
+	type MigrationTxPriority = ConstU64<{ u64::MAX / 2 }>;
+	type MigrationTxLongevity = ConstU64<64>;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type PalletId = MigrationPalletId;
+	type MaxSponsoredPallets = ConstU32<4>;
+	type SourceBalanceEntry = SystemAccountEntry;
+	type MaxProofNodes = ConstU32<16>;
+	type MaxSourceAuthorities = ConstU32<8>;
+	type SourceBurns = SourceBurns;
+	type Erc20Source = Erc20Token;
+	type MaxReceiptLength = ConstU32<16_384>;
+	type OnMigrated = MigrationLog;
+	type AttesterId = crate::crypto::Public;
+	type MaxAttesters = ConstU32<8>;
+	type MaxQueuedMigrations = ConstU32<4>;
+	type MaxCategoryNameLength = ConstU32<16>;
+	type MaxCategories = ConstU32<2>;
+	type SourceSignature = Signature;
+	type DisputeBond = ConstU128<100>;
+	type MaxDisputedCredits = ConstU32<4>;
+	type DisputeSlash = ();
 
 	#[cfg(feature = "runtime-benchmarks")]
-	type BenchmarkHelper = ();
+	type BenchmarkHelper = MigrationBenchmarkHelper;
 	#[cfg(feature = "runtime-benchmarks")]
 	type AssetIdParameter = codec::Compact<u32>;
 }
 
+/// A second, independent migration from an Ethereum based chain.
+impl pallet_migration::Config<Instance1> for TestSuite {
+	type TokenId = u32;
+	type RuntimeEvent = RuntimeEvent;
+	type AssetToCurrencyBalance = Identity;
+	type CurrencyToAssetBalance = Identity;
+	type LocalToken = Balances;
+	type Assets = Assets;
+	type AssetBalance = <pallet_balances::Pallet<TestSuite> as Currency<AccountId>>::Balance;
+	type MigrationVaultAccount = Public;
+	type MigrationOwner = Public;
+	type WeightInfo = ();
+	type SourceAccountId = H160;
+	type MaxSourceRefLength = ConstU32<32>;
+	type AuthorityId = crate::crypto::MigrationAuthId;
+	type OffchainRetryDelay = ConstU64<5>;
+	type MaxOffchainRetries = ConstU32<3>;
+	type MaxOffchainSubmissions = ConstU32<2>;
+	type MaxAllocationChunk = ConstU32<100>;
+	type MigrationTxPriority = ConstU64<{ u64::MAX / 2 }>;
+	type MigrationTxLongevity = ConstU64<64>;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type PalletId = EthMigrationPalletId;
+	type MaxSponsoredPallets = ConstU32<4>;
+	type SourceBalanceEntry = SystemAccountEntry;
+	type MaxProofNodes = ConstU32<16>;
+	type MaxSourceAuthorities = ConstU32<8>;
+	type SourceBurns = ();
+	type Erc20Source = Erc20Token;
+	type MaxReceiptLength = ConstU32<16_384>;
+	type OnMigrated = ();
+	type AttesterId = crate::crypto::Public;
+	type MaxAttesters = ConstU32<8>;
+	type MaxQueuedMigrations = ConstU32<4>;
+	type MaxCategoryNameLength = ConstU32<16>;
+	type MaxCategories = ConstU32<2>;
+	type SourceSignature = sp_core::ecdsa::Signature;
+	type DisputeBond = ConstU128<100>;
+	type MaxDisputedCredits = ConstU32<4>;
+	type DisputeSlash = ();
+
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MigrationBenchmarkHelper;
+	#[cfg(feature = "runtime-benchmarks")]
+	type AssetIdParameter = codec::Compact<u32>;
+}
+
+impl frame_system::offchain::SigningTypes for TestSuite {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for TestSuite
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for TestSuite
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: <Signature as Verify>::Signer,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
 /// Existential deposit.
 pub const EXISTENTIAL_DEPOSIT: u128 = 1;
 /// Balance of an account.
@@ -113,16 +362,30 @@ impl pallet_assets::Config for TestSuite {
 	type MetadataDepositPerByte = ConstU128<1>;
 	type ApprovalDeposit = ConstU128<1>;
 	type StringLimit = ConstU32<50>;
-	type Freezer = ();
+	type Freezer = pallet_migration::CombinedFreezer<Migration, MigrationEth>;
 	type Extra = ();
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<TestSuite>;
 	type RemoveItemsLimit = ConstU32<1000>;
 	type CallbackHandle = ();
 }
 
+impl pallet_transaction_payment::Config for TestSuite {
+	type RuntimeEvent = RuntimeEvent;
+	type OnChargeTransaction = pallet_migration::SponsoredCurrencyAdapter<TestSuite, (), pallet_transaction_payment::CurrencyAdapter<Balances, ()>>;
+	type OperationalFeeMultiplier = ConstU8<5>;
+	type WeightToFee = IdentityFee<Balance>;
+	type LengthToFee = IdentityFee<Balance>;
+	type FeeMultiplierUpdate = ();
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 pub fn new_default_ext() -> sp_io::TestExternalities {
-	frame_system::GenesisConfig::default().build_storage::<TestSuite>().unwrap().into()
+	use sp_keystore::{testing::KeyStore, KeystoreExt};
+
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default().build_storage::<TestSuite>().unwrap().into();
+	// Source-chain authorities sign justifications with keys of the keystore.
+	ext.register_extension(KeystoreExt(std::sync::Arc::new(KeyStore::new())));
+	ext
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -145,16 +408,30 @@ pub fn new_benchmark_ext() -> sp_io::TestExternalities {
 	new_test_ext(users, caller, vault_total, migration_vault_account.clone(), migration_owner_account.clone())
 }
 
+pub fn eth_migration_vault_account() -> AccountId {
+	crate::helpers::get_account_id_from_seed::<Public>("EthMigrationVault")
+}
+
+pub fn eth_migration_owner_account() -> AccountId {
+	crate::helpers::get_account_id_from_seed::<Public>("EthMigrationOwner")
+}
+
 pub fn new_test_ext(users: Vec<AccountId>, root_key: AccountId, vault_total: u128, migration_vault_account: AccountId, migration_owner_account: AccountId) -> sp_io::TestExternalities {
 	let mut storage = frame_system::GenesisConfig::default().build_storage::<TestSuite>().unwrap();
 
 	GenesisConfig {
 		assets: AssetsConfig {
-			assets: vec!((TOKEN_ID, root_key.clone(), true, 1)),
+			assets: vec!((TOKEN_ID, root_key.clone(), true, 1), (ETH_TOKEN_ID, root_key.clone(), true, 1)),
 			/// Genesis metadata: id, name, symbol, decimals
-			metadata: vec!((TOKEN_ID, "Jur token".as_bytes().to_vec(), "JUR".as_bytes().to_vec(), 12)),
+			metadata: vec!(
+				(TOKEN_ID, "Jur token".as_bytes().to_vec(), "JUR".as_bytes().to_vec(), 12),
+				(ETH_TOKEN_ID, "Jur ERC-20 token".as_bytes().to_vec(), "eJUR".as_bytes().to_vec(), 18),
+			),
 			/// Genesis accounts: id, account_id, balance
-			accounts: vec!((TOKEN_ID, migration_vault_account.clone(), vault_total)),
+			accounts: vec!(
+				(TOKEN_ID, migration_vault_account.clone(), vault_total),
+				(ETH_TOKEN_ID, eth_migration_vault_account(), vault_total),
+			),
 
 		},
 		balances: BalancesConfig {
@@ -164,7 +441,16 @@ pub fn new_test_ext(users: Vec<AccountId>, root_key: AccountId, vault_total: u12
 		migration: MigrationConfig {
 			migration_vault_account: Some(migration_vault_account),
 			migration_owner: Some(migration_owner_account),
-			asset_id: Some(TOKEN_ID)
+			asset_id: Some(TOKEN_ID),
+			transfer_lock_until: None,
+			source_state_roots: vec![],
+		},
+		migration_eth: MigrationEthConfig {
+			migration_vault_account: Some(eth_migration_vault_account()),
+			migration_owner: Some(eth_migration_owner_account()),
+			asset_id: Some(ETH_TOKEN_ID),
+			transfer_lock_until: None,
+			source_state_roots: vec![],
 		},
 		..Default::default()
 	}
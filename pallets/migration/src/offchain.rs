@@ -0,0 +1,217 @@
+//! Off-chain worker submitting migrations from a queue in offchain local storage.
+//!
+//! The queue is a SCALE encoded `Vec<PendingMigration>` stored in `PERSISTENT` local storage
+//! under [`Pallet::offchain_queue_key`], which is prefixed with the pallet's name in the runtime so
+//! every instance has its own queue. It is filled by the operator through the
+//! `offchain_localStorageSet` RPC or the node's `--migration-queue` flag. Every item must carry a
+//! `source_ref` so the worker can tell from [`ProcessedSourceRefs`] whether it made it on chain.
+
+use crate::pallet::*;
+use codec::{Decode, Encode};
+use frame_support::traits::{Get, PalletInfoAccess};
+use frame_system::offchain::{AppCrypto, SendSignedTransaction, Signer};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{StorageLock, Time},
+	},
+	traits::{IdentifyAccount, Saturating},
+	RuntimeAppPublic, RuntimeDebug,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec, vec::Vec};
+
+const OFFCHAIN_QUEUE_KEY: &[u8] = b"pending";
+const OFFCHAIN_FAILED_KEY: &[u8] = b"failed";
+const OFFCHAIN_SUBMISSIONS_KEY: &[u8] = b"submissions";
+const OFFCHAIN_NONCE_KEY: &[u8] = b"nonce";
+const OFFCHAIN_LOCK_KEY: &[u8] = b"lock";
+
+/// A migration waiting in the off-chain queue.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PendingMigration<SourceAccountId, AccountId, Balance> {
+	pub for_account: SourceAccountId,
+	pub account_to_credit: AccountId,
+	pub amount: Balance,
+	pub source_ref: Vec<u8>,
+	/// Name of the allocation category to migrate from, see [`Pallet::migrate`].
+	pub category: Option<Vec<u8>>,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, Default)]
+struct Submission<BlockNumber> {
+	submitted_at: BlockNumber,
+	attempts: u32,
+}
+
+#[derive(RuntimeDebug)]
+pub(crate) enum OffchainError {
+	Locked,
+	NotConfigured,
+	NoOwnerKey,
+	StorageRetrieval,
+}
+
+type PendingMigrationOf<T, I> = PendingMigration<
+	<T as Config<I>>::SourceAccountId,
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T, I>,
+>;
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Submit the due items of the off-chain queue, returning how many were submitted.
+	pub(crate) fn process_offchain_queue(now: T::BlockNumber) -> Result<u32, OffchainError> {
+		let queue_key = Self::offchain_queue_key();
+		let queue_ref = StorageValueRef::persistent(&queue_key);
+		let queue = queue_ref
+			.get::<Vec<PendingMigrationOf<T, I>>>()
+			.map_err(|_| OffchainError::StorageRetrieval)?
+			.unwrap_or_default();
+		if queue.is_empty() {
+			return Ok(0)
+		}
+
+		let lock_key = Self::offchain_key(OFFCHAIN_LOCK_KEY);
+		let mut lock = StorageLock::<Time>::new(&lock_key);
+		let _guard = lock.try_lock().map_err(|_| OffchainError::Locked)?;
+
+		let owner = <MigrationOwner<T, I>>::get().ok_or(OffchainError::NotConfigured)?;
+		let owner_key = Self::local_owner_key(&owner).ok_or(OffchainError::NoOwnerKey)?;
+
+		let submissions_key = Self::offchain_key(OFFCHAIN_SUBMISSIONS_KEY);
+		let submissions_ref = StorageValueRef::persistent(&submissions_key);
+		let mut submissions = submissions_ref
+			.get::<BTreeMap<Vec<u8>, Submission<T::BlockNumber>>>()
+			.map_err(|_| OffchainError::StorageRetrieval)?
+			.unwrap_or_default();
+
+		let mut done = Vec::new();
+		let mut failed = Vec::new();
+		let mut due = Vec::new();
+		let mut retrying = false;
+		for item in queue {
+			let source_ref = SourceRef::<T, I>::try_from(item.source_ref.clone());
+			let category = item.category.clone().map(CategoryName::<T, I>::try_from).transpose();
+			let (source_ref, category) = match (source_ref, category) {
+				(Ok(source_ref), Ok(category)) => (source_ref, category),
+				_ => {
+					failed.push(item);
+					continue
+				},
+			};
+			if <ProcessedSourceRefs<T, I>>::contains_key(&source_ref) {
+				submissions.remove(&item.source_ref);
+				done.push(item.source_ref);
+				continue
+			}
+			match submissions.get(&item.source_ref) {
+				None => due.push((item, source_ref, category)),
+				Some(s) if now.saturating_sub(s.submitted_at) >= T::OffchainRetryDelay::get() => {
+					if s.attempts >= T::MaxOffchainRetries::get() {
+						submissions.remove(&item.source_ref);
+						failed.push(item);
+					} else {
+						retrying = true;
+						due.push((item, source_ref, category));
+					}
+				},
+				Some(_) => {},
+			}
+		}
+
+		// Continue from the last nonce we signed with, so transactions still in the pool are not
+		// replaced. A due retry means an earlier submission was dropped before inclusion, in which
+		// case the nonces after it were never used and we restart from the on-chain one.
+		let nonce_key = Self::offchain_key(OFFCHAIN_NONCE_KEY);
+		let nonce_ref = StorageValueRef::persistent(&nonce_key);
+		let on_chain_nonce = <frame_system::Pallet<T>>::account_nonce(&owner);
+		let next_nonce = match nonce_ref.get::<T::Index>().ok().flatten() {
+			Some(tracked) if !retrying && tracked > on_chain_nonce => tracked,
+			_ => on_chain_nonce,
+		};
+		// `Signer` takes the nonce from `frame_system::Account` and bumps it after each
+		// submission. Runtime storage writes made by an off-chain worker are never persisted.
+		<frame_system::Account<T>>::mutate(&owner, |account| account.nonce = next_nonce);
+
+		let signer = Signer::<T, <T as Config<I>>::AuthorityId>::any_account().with_filter(vec![owner_key]);
+		let mut submitted = 0u32;
+		for (item, source_ref, category) in due.into_iter().take(T::MaxOffchainSubmissions::get() as usize) {
+			let result = signer.send_signed_transaction(|_| Call::<T, I>::migrate {
+				for_account: item.for_account.clone(),
+				account_to_credit: item.account_to_credit.clone(),
+				amount: item.amount,
+				source_ref: Some(source_ref.clone()),
+				category: category.clone(),
+			});
+			match result {
+				Some((_, Ok(()))) => {
+					let submission = submissions.entry(item.source_ref).or_default();
+					submission.submitted_at = now;
+					submission.attempts = submission.attempts.saturating_add(1);
+					submitted += 1;
+				},
+				_ => log::warn!(
+					target: "runtime::migration",
+					"failed to submit migration with source_ref {:?}",
+					item.source_ref,
+				),
+			}
+		}
+
+		nonce_ref.set(&<frame_system::Pallet<T>>::account_nonce(&owner));
+		submissions_ref.set(&submissions);
+
+		if !failed.is_empty() {
+			let failed_key = Self::offchain_failed_key();
+			let failed_ref = StorageValueRef::persistent(&failed_key);
+			let mut all_failed =
+				failed_ref.get::<Vec<PendingMigrationOf<T, I>>>().ok().flatten().unwrap_or_default();
+			done.extend(failed.iter().map(|item| item.source_ref.clone()));
+			all_failed.extend(failed);
+			failed_ref.set(&all_failed);
+		}
+
+		if !done.is_empty() {
+			// The operator may have appended to the queue in the meantime, so only drop what we
+			// have finished with instead of overwriting the whole value.
+			let _ = queue_ref.mutate(
+				|queue: Result<Option<Vec<PendingMigrationOf<T, I>>>, _>| -> Result<_, ()> {
+					let mut queue = queue.ok().flatten().unwrap_or_default();
+					queue.retain(|item| !done.contains(&item.source_ref));
+					Ok(queue)
+				},
+			);
+		}
+
+		Ok(submitted)
+	}
+
+	/// Local storage key of the queue of migrations waiting to be submitted, e.g.
+	/// `Migration::pending`.
+	pub fn offchain_queue_key() -> Vec<u8> {
+		Self::offchain_key(OFFCHAIN_QUEUE_KEY)
+	}
+
+	/// Local storage key of the migrations given up on after too many attempts, e.g.
+	/// `Migration::failed`.
+	pub fn offchain_failed_key() -> Vec<u8> {
+		Self::offchain_key(OFFCHAIN_FAILED_KEY)
+	}
+
+	fn offchain_key(suffix: &[u8]) -> Vec<u8> {
+		[<Self as PalletInfoAccess>::name().as_bytes(), b"::", suffix].concat()
+	}
+
+	/// The key in the local keystore that belongs to the migration owner, if any.
+	fn local_owner_key(owner: &T::AccountId) -> Option<T::Public> {
+		<<T as Config<I>>::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+			.into_iter()
+			.map(|key| {
+				let generic: <<T as Config<I>>::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic =
+					key.into();
+				let public: T::Public = generic.into();
+				public
+			})
+			.find(|public| &public.clone().into_account() == owner)
+	}
+}
@@ -0,0 +1,148 @@
+//! Transaction pool checks for `migrate` calls.
+
+use crate::pallet::*;
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, IsSubType},
+	CloneNoBound, EqNoBound, PartialEqNoBound,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, Saturating, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::{fmt, marker::PhantomData};
+
+/// `InvalidTransaction::Custom` code: the allocation table is committed but not sealed yet.
+pub const ALLOCATIONS_NOT_SEALED: u8 = 1;
+/// `InvalidTransaction::Custom` code: the source account has no registered allocation.
+pub const NO_ALLOCATION: u8 = 2;
+/// `InvalidTransaction::Custom` code: the migration would exceed the registered allocation.
+pub const ALLOCATION_EXCEEDED: u8 = 3;
+/// `InvalidTransaction::Custom` code: the holder bound another destination to the source account.
+pub const DESTINATION_MISMATCH: u8 = 4;
+
+/// Validates `migrate` calls before they enter the transaction pool.
+///
+/// Calls from anyone but the migration owner, with an already used `source_ref`, to another
+/// destination than the one bound by the holder or beyond the sealed allocation of the source
+/// account are rejected. Valid calls with a `source_ref` provide a tag per source account and
+/// `source_ref`, so duplicates are dropped from the pool. The checks run again right before
+/// dispatch, so invalid migrations are never included in a block.
+///
+/// Only a top-level `migrate` is checked. A `migrate` wrapped in another call, e.g. sent through
+/// `Proxy::proxy` or `Multisig::as_multi`, bypasses the pool checks and gets no tag; it is still
+/// validated by `migrate` itself when dispatched.
+///
+/// A runtime with several migration instances adds one extension per instance.
+#[derive(Encode, Decode, CloneNoBound, EqNoBound, PartialEqNoBound, TypeInfo)]
+#[scale_info(skip_type_params(T, I))]
+pub struct CheckMigration<T: Config<I> + Send + Sync, I: 'static + Send + Sync = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> CheckMigration<T, I> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> Default for CheckMigration<T, I> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> fmt::Debug for CheckMigration<T, I> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckMigration")
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> SignedExtension for CheckMigration<T, I>
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T, I>>,
+{
+	const IDENTIFIER: &'static str = "CheckMigration";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		match call.is_sub_type() {
+			Some(Call::migrate { for_account, account_to_credit, amount, source_ref, .. }) =>
+				Pallet::<T, I>::validate_migration(who, for_account, account_to_credit, *amount, source_ref.as_ref()),
+			_ => Ok(ValidTransaction::default()),
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	fn validate_migration(
+		who: &T::AccountId,
+		for_account: &T::SourceAccountId,
+		account_to_credit: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		source_ref: Option<&SourceRef<T, I>>,
+	) -> TransactionValidity {
+		let owner = <MigrationOwner<T, I>>::get().ok_or(InvalidTransaction::Call)?;
+		if &owner != who {
+			return Err(InvalidTransaction::BadSigner.into())
+		}
+
+		if let Some(source_ref) = source_ref {
+			if <ProcessedSourceRefs<T, I>>::contains_key(source_ref) {
+				return Err(InvalidTransaction::Stale.into())
+			}
+		}
+
+		if let Some(destination) = <BoundDestinations<T, I>>::get(for_account) {
+			if &destination != account_to_credit {
+				return Err(InvalidTransaction::Custom(DESTINATION_MISMATCH).into())
+			}
+		}
+
+		if <AllocationCommitment<T, I>>::exists() {
+			if !Self::allocations_sealed() {
+				return Err(InvalidTransaction::Custom(ALLOCATIONS_NOT_SEALED).into())
+			}
+			let allocated = <Allocations<T, I>>::get(for_account)
+				.ok_or(InvalidTransaction::Custom(NO_ALLOCATION))?;
+			if <MigratedAmounts<T, I>>::get(for_account).saturating_add(amount) > allocated {
+				return Err(InvalidTransaction::Custom(ALLOCATION_EXCEEDED).into())
+			}
+		}
+
+		let valid = ValidTransaction::with_tag_prefix("Migration")
+			.priority(<T as Config<I>>::MigrationTxPriority::get())
+			.longevity(<T as Config<I>>::MigrationTxLongevity::get())
+			.propagate(true);
+		// Without a `source_ref` nothing identifies a duplicate, and two migrations for the same
+		// source account must not replace each other in the pool.
+		match source_ref {
+			Some(source_ref) => valid.and_provides((<T as Config<I>>::PalletId::get(), for_account, source_ref)).build(),
+			None => valid.build(),
+		}
+	}
+}
@@ -0,0 +1,71 @@
+//! Fee payment by sponsors of migration recipients.
+
+use crate::pallet::*;
+use codec::Encode;
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, Saturating, Zero},
+	transaction_validity::TransactionValidityError,
+};
+use sp_std::marker::PhantomData;
+
+/// Charges the fees of a sponsored migration recipient to its sponsor, and all other fees to the
+/// sender, through `OCT`, e.g. `CurrencyAdapter<Balances, ()>`.
+///
+/// A call is sponsored while the sponsor is registered, the call's pallet is one of the sponsored
+/// pallets, the call has no tip and the fee fits in what is left of the sponsorship's cap. Only
+/// the pallet of the outer call counts: a call of a sponsored pallet wrapped in a `Proxy`,
+/// `Multisig` or `Utility` call is charged to the sender, unless the wrapping pallet is sponsored
+/// too. Tips are never sponsored, so that a sponsored account cannot drain the cap to the block
+/// author; a tipping sender pays the whole fee itself. When the sponsor can't pay the fee, the
+/// sender pays it and the cap is left untouched. Refunds after dispatch go back to the sponsor and
+/// its cap.
+pub struct SponsoredCurrencyAdapter<T, I, OCT>(PhantomData<(T, I, OCT)>);
+
+impl<T, I, OCT> OnChargeTransaction<T> for SponsoredCurrencyAdapter<T, I, OCT>
+where
+	T: Config<I> + pallet_transaction_payment::Config,
+	I: 'static,
+	OCT: OnChargeTransaction<T, Balance = BalanceOf<T, I>>,
+{
+	type Balance = BalanceOf<T, I>;
+	/// Sponsor and fee charged to it, if sponsored.
+	type LiquidityInfo = (Option<(T::AccountId, Self::Balance)>, OCT::LiquidityInfo);
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		// The first byte of an encoded runtime call is the index of its pallet.
+		let pallet_index = call.using_encoded(|encoded| encoded[0]);
+		let sponsor = if tip.is_zero() { Pallet::<T, I>::sponsor_for(who, pallet_index, fee) } else { None };
+		if let Some(sponsor) = sponsor {
+			if let Ok(withdrawn) = OCT::withdraw_fee(&sponsor, call, info, fee, tip) {
+				Pallet::<T, I>::charge_sponsorship(who, fee);
+				return Ok((Some((sponsor, fee)), withdrawn))
+			}
+		}
+		Ok((None, OCT::withdraw_fee(who, call, info, fee, tip)?))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		(sponsored, already_withdrawn): Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		match sponsored {
+			Some((sponsor, fee)) => {
+				OCT::correct_and_deposit_fee(&sponsor, dispatch_info, post_info, corrected_fee, tip, already_withdrawn)?;
+				Pallet::<T, I>::refund_sponsorship(who, fee.saturating_sub(corrected_fee));
+				Ok(())
+			},
+			None => OCT::correct_and_deposit_fee(who, dispatch_info, post_info, corrected_fee, tip, already_withdrawn),
+		}
+	}
+}
@@ -0,0 +1,103 @@
+//! Balances proven against state roots of a Substrate source chain.
+//!
+//! The source chain is expected to hash its storage with `BlakeTwo256` and keep balances of
+//! `u128` in either `System::Account` or `Assets::Account`, as a default Substrate runtime does.
+
+use crate::pallet::*;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	ensure,
+	traits::Get,
+	Blake2_128Concat, StorageHasher, Twox128,
+};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{traits::BlakeTwo256, ArithmeticError, DispatchError, RuntimeDebug};
+use sp_std::vec::Vec;
+use sp_trie::{read_trie_value, LayoutV1, StorageProof};
+
+/// Storage entry of the source chain holding the balances of holders.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum SourceBalanceEntry {
+	/// `System::Account`, the free and reserved balance of the native token.
+	System,
+	/// `Assets::Account` of the given asset.
+	Assets(u32),
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Source-chain storage key of the balance of `for_account`.
+	pub fn source_balance_key(for_account: &T::SourceAccountId) -> Vec<u8> {
+		let account = for_account.using_encoded(Blake2_128Concat::hash);
+		match T::SourceBalanceEntry::get() {
+			SourceBalanceEntry::System =>
+				[Twox128::hash(b"System").to_vec(), Twox128::hash(b"Account").to_vec(), account].concat(),
+			SourceBalanceEntry::Assets(id) => [
+				Twox128::hash(b"Assets").to_vec(),
+				Twox128::hash(b"Account").to_vec(),
+				id.using_encoded(Blake2_128Concat::hash),
+				account,
+			]
+			.concat(),
+		}
+	}
+
+	/// Value at `key` proven by the trie nodes in `proof` against the source-chain state `root`.
+	pub(crate) fn read_source_storage(
+		root: &H256,
+		key: &[u8],
+		proof: Vec<Vec<u8>>,
+	) -> Result<Option<Vec<u8>>, Error<T, I>> {
+		let db = StorageProof::new(proof).into_memory_db::<BlakeTwo256>();
+		read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, root, key, None, None)
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)
+	}
+
+	/// Balance of `for_account` proven by the trie nodes in `proof` against the source-chain
+	/// state `root`.
+	pub fn prove_source_balance(
+		root: &H256,
+		for_account: &T::SourceAccountId,
+		proof: Vec<Vec<u8>>,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		let key = Self::source_balance_key(for_account);
+		let value = Self::read_source_storage(root, &key, proof)?.ok_or(Error::<T, I>::NoProvenBalance)?;
+
+		let balance = match T::SourceBalanceEntry::get() {
+			// `AccountInfo { nonce, consumers, providers, sufficients, data: AccountData { free, reserved, .. } }`
+			SourceBalanceEntry::System => {
+				let (_, _, _, _, free, reserved) = <(u32, u32, u32, u32, u128, u128)>::decode(&mut &value[..])
+					.map_err(|_| Error::<T, I>::InvalidStorageProof)?;
+				free.saturating_add(reserved)
+			},
+			// `AssetAccount { balance, .. }`
+			SourceBalanceEntry::Assets(_) =>
+				u128::decode(&mut &value[..]).map_err(|_| Error::<T, I>::InvalidStorageProof)?,
+		};
+		ensure!(balance > 0, Error::<T, I>::NoProvenBalance);
+		balance.try_into().map_err(|_| ArithmeticError::Overflow.into())
+	}
+}
+
+/// State root of a source-chain trie holding `entries`, and all its nodes as a proof.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn build_source_state(entries: &[(Vec<u8>, Vec<u8>)]) -> (H256, Vec<Vec<u8>>) {
+	use sp_trie::{MemoryDB, TrieDBMutBuilder, TrieMut};
+
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut root = H256::default();
+	{
+		let mut trie = TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+		for (key, value) in entries {
+			trie.insert(key, value).expect("in-memory trie; qed");
+		}
+	}
+	(root, db.drain().into_iter().map(|(_, (node, _))| node).collect())
+}
+
+/// Source-chain `System::Account` value with the given free balance.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn source_account_info(free: u128) -> Vec<u8> {
+	// nonce, consumers, providers, sufficients, free, reserved, misc_frozen, fee_frozen
+	(0u32, 0u32, 1u32, 0u32, free, 0u128, 0u128, 0u128).encode()
+}
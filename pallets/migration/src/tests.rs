@@ -1,6 +1,14 @@
-use crate::{mock::*, helpers::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
-use sp_core::{sr25519};
+use crate::{mock::*, helpers::*, Error, AllocationChunk, CheckMigration, SponsoredPallets, StorageProofOf, ReceiptOf, storage_proof::{build_source_state, source_account_info}, light_client::{build_justification, source_events_entry}, ethereum::{build_receipts_trie, erc20_burn_receipt, erc20_burns_receipt, ethereum_header}, binding::{ethereum_signer, personal_message_hash}, SourceHeader, signed_extension::{ALLOCATIONS_NOT_SEALED, ALLOCATION_EXCEEDED, DESTINATION_MISMATCH, NO_ALLOCATION}, MigrationRequest, MigrationDryRunOutcome, SourceRef, PendingMigration, AttestersOf, AttestationsOf, OutboundRelease, QueuedMigration, MigrationBatch, MigrationQueueProgress, CategoryName, CategoryVesting, CategoryTotals, DisputeStatus, KEY_TYPE};
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok, instances::Instance1, unsigned::ValidateUnsigned, traits::{tokens::BalanceConversion, Currency, Get, Hooks, PalletInfoAccess}, dispatch::{DispatchClass, GetDispatchInfo, Pays}, weights::Weight};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{traits::{Dispatchable, Header as HeaderT, SignedExtension}, transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError}, DigestItem, FixedU128};
+use sp_finality_grandpa::{AuthorityId as GrandpaId, AuthoritySignature, ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+use sp_core::{ecdsa, ed25519, sr25519, H160, H256, Pair, offchain::{testing::{TestOffchainExt, TestTransactionPoolExt}, OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt}};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use std::sync::Arc;
+
+type Event = crate::Event<TestSuite>;
 
 #[test]
 fn correct_error_for_unauthorised() {
@@ -18,7 +26,7 @@ fn correct_error_for_unauthorised() {
 
 		// Ensure the expected error is thrown when no value is present.
 		assert_noop!(
-			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account3.clone().into(), account3.clone(), 50),
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account3.clone().into(), account3.clone(), 50, None, None),
 			Error::<TestSuite>::Unauthorised
 		);
 	});
@@ -41,7 +49,7 @@ fn migration_should_work_for_correct_values() {
 		System::set_block_number(1);
 
 		// Dispatch a signed extrinsic.
-		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount, None, None));
 
 		let token_id = Migration::get_token_id().unwrap();
 		
@@ -59,7 +67,8 @@ fn migration_should_work_for_correct_values() {
 			for_account: account2.clone().into(),
 			to_account: account3.clone().into(),
 			vault_balance_remained: vault_total - migrate_amount,
-			account_balance_after: migrate_amount
+			account_balance_after: migrate_amount,
+			source_ref: None
 		}.into());
 	});
 }
@@ -82,7 +91,7 @@ fn double_migration_should_work_for_correct_values() {
 		System::set_block_number(1);
 
 		// Dispatch a signed extrinsic.
-		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount1));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount1, None, None));
 
 		let token_id = Migration::get_token_id().unwrap();
 		
@@ -100,11 +109,12 @@ fn double_migration_should_work_for_correct_values() {
 			for_account: account2.clone().into(),
 			to_account: account3.clone().into(),
 			vault_balance_remained: vault_total - migrate_amount1,
-			account_balance_after: migrate_amount1
+			account_balance_after: migrate_amount1,
+			source_ref: None
 		}.into());
 
 		// Dispatch a signed extrinsic.
-		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount2));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount2, None, None));
 
 		// Read pallet storage and assert an expected result.
 		vault_balance = Assets::balance(token_id, &migration_vault_account);
@@ -120,7 +130,2068 @@ fn double_migration_should_work_for_correct_values() {
 			for_account: account2.clone().into(),
 			to_account: account3.clone().into(),
 			vault_balance_remained: vault_total - migrate_amount1 - migrate_amount2,
-			account_balance_after: migrate_amount1 + migrate_amount2
+			account_balance_after: migrate_amount1 + migrate_amount2,
+			source_ref: None
+		}.into());
+	});
+}
+
+
+#[test]
+fn dry_run_reports_outcomes_without_changing_state() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let vault_total = 1000;
+
+	new_test_ext(users, signer.clone(), vault_total, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+
+		let outcomes = Migration::dry_run_migrate(signer.clone(), vec![
+			MigrationRequest { for_account: account2.clone().into(), account_to_credit: account3.clone(), amount: 600, source_ref: None, category: None },
+			MigrationRequest { for_account: account2.clone().into(), account_to_credit: account3.clone(), amount: 600, source_ref: None, category: None },
+			MigrationRequest { for_account: account2.clone().into(), account_to_credit: account3.clone(), amount: 300, source_ref: None, category: None },
+		]);
+
+		assert_eq!(outcomes.len(), 3);
+		assert_eq!(outcomes[0], MigrationDryRunOutcome::Success { vault_balance_remained: 400, account_balance_after: 600 });
+		// Later items see the effect of the earlier ones, so the vault can no longer cover it.
+		assert!(matches!(outcomes[1], MigrationDryRunOutcome::Failed(_)));
+		assert_eq!(outcomes[2], MigrationDryRunOutcome::Success { vault_balance_remained: 100, account_balance_after: 900 });
+
+		// Nothing was written.
+		assert_eq!(Assets::balance(token_id, &migration_vault_account), vault_total);
+		assert_eq!(Assets::balance(token_id, &account3), 0);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn dry_run_reports_unauthorised_signer() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account2.clone();
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		let outcomes = Migration::dry_run_migrate(signer.clone(), vec![
+			MigrationRequest { for_account: account3.clone().into(), account_to_credit: account3.clone(), amount: 50, source_ref: None, category: None },
+		]);
+
+		assert_eq!(outcomes, vec![MigrationDryRunOutcome::Failed(Error::<TestSuite>::Unauthorised.into())]);
+	});
+}
+
+#[test]
+fn dry_run_uses_the_limits_of_its_instance() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		// Source references of the default instance may be 64 bytes long, those of `Instance1` 32.
+		let outcomes = MigrationEth::dry_run_migrate(eth_migration_owner_account(), vec![
+			MigrationRequest { for_account: H160::repeat_byte(1), account_to_credit: account3.clone(), amount: 50, source_ref: Some(vec![1; 32]), category: None },
+			MigrationRequest { for_account: H160::repeat_byte(1), account_to_credit: account3.clone(), amount: 50, source_ref: Some(vec![2; 40]), category: None },
+		]);
+
+		assert_eq!(outcomes, vec![
+			MigrationDryRunOutcome::Success { vault_balance_remained: 950, account_balance_after: 50 },
+			MigrationDryRunOutcome::Failed(Error::<TestSuite, Instance1>::SourceRefTooLong.into()),
+		]);
+		assert_eq!(Assets::balance(ETH_TOKEN_ID, &account3), 0);
+	});
+}
+
+#[test]
+fn migration_with_used_source_ref_is_rejected() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let vault_total = 1000;
+	let migrate_amount = 50;
+	let source_ref: SourceRef<TestSuite> = b"0xburn-event-1".to_vec().try_into().unwrap();
+
+	new_test_ext(users, signer.clone(), vault_total, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount, Some(source_ref.clone()), None));
+
+		System::assert_last_event(Event::BalanceMigrated { 
+			amount: migrate_amount,
+			from_vault: migration_vault_account.clone(),
+			for_account: account2.clone().into(),
+			to_account: account3.clone().into(),
+			vault_balance_remained: vault_total - migrate_amount,
+			account_balance_after: migrate_amount,
+			source_ref: Some(source_ref.clone())
+		}.into());
+		assert_eq!(Migration::processed_source_ref(&source_ref), Some(1));
+
+		// A retry of the same source transfer must not credit twice.
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), migrate_amount, Some(source_ref.clone()), None),
+			Error::<TestSuite>::SourceRefAlreadyUsed
+		);
+
+		let token_id = Migration::get_token_id().unwrap();
+		assert_eq!(Assets::balance(token_id, &account3), migrate_amount);
+	});
+}
+
+const PHRASE: &str = "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+
+fn pending(for_account: &AccountId, account_to_credit: &AccountId, amount: u128, source_ref: &[u8]) -> PendingMigration<SourceAccount, AccountId, u128> {
+	PendingMigration {
+		for_account: for_account.clone().into(),
+		account_to_credit: account_to_credit.clone(),
+		amount,
+		source_ref: source_ref.to_vec(),
+		category: None,
+	}
+}
+
+fn submitted_calls(transactions: &[Vec<u8>]) -> Vec<(Option<(u64, ())>, RuntimeCall)> {
+	transactions
+		.iter()
+		.map(|tx| {
+			let tx = Extrinsic::decode(&mut &tx[..]).unwrap();
+			(tx.signature, tx.call)
+		})
+		.collect()
+}
+
+#[test]
+fn offchain_worker_submits_queued_migrations_signed_by_owner() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	let keystore = KeyStore::new();
+	let owner_public = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, Some(&format!("{}/owner", PHRASE))).unwrap();
+	let migration_owner_account: AccountId = owner_public.into();
+
+	let mut ext = new_test_ext(users, account1.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone());
+	let (offchain, _offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	ext.execute_with(|| {
+		let queue = vec![
+			pending(&account2, &account3, 10, b"ref-1"),
+			PendingMigration { category: Some(b"team".to_vec()), ..pending(&account2, &account3, 20, b"ref-2") },
+			pending(&account2, &account3, 30, b"ref-3"),
+		];
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, &Migration::offchain_queue_key(), &queue.encode());
+
+		Migration::offchain_worker(1);
+
+		// `MaxOffchainSubmissions` caps the submissions per block.
+		let calls = submitted_calls(&pool_state.read().transactions);
+		assert_eq!(calls, vec![
+			(Some((0, ())), RuntimeCall::Migration(crate::Call::migrate {
+				for_account: account2.clone().into(),
+				account_to_credit: account3.clone(),
+				amount: 10,
+				source_ref: Some(b"ref-1".to_vec().try_into().unwrap()),
+				category: None,
+			})),
+			(Some((1, ())), RuntimeCall::Migration(crate::Call::migrate {
+				for_account: account2.clone().into(),
+				account_to_credit: account3.clone(),
+				amount: 20,
+				source_ref: Some(b"ref-2".to_vec().try_into().unwrap()),
+				category: Some(b"team".to_vec().try_into().unwrap()),
+			})),
+		]);
+	});
+}
+
+#[test]
+fn offchain_worker_tracks_nonces_and_drops_processed_migrations() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	let keystore = KeyStore::new();
+	let owner_public = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, Some(&format!("{}/owner", PHRASE))).unwrap();
+	let migration_owner_account: AccountId = owner_public.into();
+
+	let mut ext = new_test_ext(users, account1.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone());
+	let (offchain, _offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	ext.execute_with(|| {
+		let queue = vec![
+			pending(&account2, &account3, 10, b"ref-1"),
+			pending(&account2, &account3, 20, b"ref-2"),
+			pending(&account2, &account3, 30, b"ref-3"),
+		];
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, &Migration::offchain_queue_key(), &queue.encode());
+
+		Migration::offchain_worker(1);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		// Only the first submission makes it into block 2.
+		System::set_block_number(2);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(migration_owner_account.clone()), account2.clone().into(), account3.clone(), 10, Some(b"ref-1".to_vec().try_into().unwrap()), None));
+		System::inc_account_nonce(&migration_owner_account);
+
+		// The remaining item continues after the nonce still pending in the pool.
+		Migration::offchain_worker(2);
+		let calls = submitted_calls(&pool_state.read().transactions);
+		assert_eq!(calls.len(), 3);
+		assert_eq!(calls[2].0, Some((2, ())));
+
+		let queue = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, OFFCHAIN_QUEUE_KEY)
+			.map(|v| Vec::<PendingMigration<SourceAccount, AccountId, u128>>::decode(&mut &v[..]).unwrap())
+			.unwrap();
+		assert_eq!(queue, vec![
+			pending(&account2, &account3, 20, b"ref-2"),
+			pending(&account2, &account3, 30, b"ref-3"),
+		]);
+
+		// Nothing is resubmitted before `OffchainRetryDelay` has passed.
+		System::set_block_number(3);
+		Migration::offchain_worker(3);
+		assert_eq!(pool_state.read().transactions.len(), 3);
+
+		// Afterwards the dropped transaction is retried from the on-chain nonce.
+		System::set_block_number(6);
+		Migration::offchain_worker(6);
+		let calls = submitted_calls(&pool_state.read().transactions);
+		assert_eq!(calls.len(), 4);
+		assert_eq!(calls[3].0, Some((1, ())));
+	});
+}
+
+fn allocation_chunk(entries: &[(SourceAccount, u128)]) -> AllocationChunk<TestSuite> {
+	entries.to_vec().try_into().unwrap()
+}
+
+#[test]
+fn sealed_allocations_limit_migrated_amounts() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let account4 = users[3].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let entries: [(SourceAccount, u128); 2] = [(account2.clone().into(), 100), (account4.clone().into(), 200)];
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let root = Migration::allocation_commitment(&entries);
+
+		assert_ok!(Migration::commit_allocations(RuntimeOrigin::signed(signer.clone()), root, 2));
+
+		// Migrations are held back until the table is sealed.
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 50, None, None),
+			Error::<TestSuite>::AllocationsNotSealed
+		);
+
+		assert_ok!(Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries[..1])));
+		assert_noop!(
+			Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())),
+			Error::<TestSuite>::AllocationCommitmentMismatch
+		);
+		assert_ok!(Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries[1..])));
+		assert_ok!(Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())));
+		System::assert_last_event(Event::AllocationsSealed { root, entries: 2 }.into());
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 60, None, None));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 40, None, None));
+		assert_eq!(Migration::migrated_amount(SourceAccount::from(account2.clone())), 100);
+
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 1, None, None),
+			Error::<TestSuite>::AllocationExceeded
+		);
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account3.clone().into(), account3.clone(), 1, None, None),
+			Error::<TestSuite>::NoAllocation
+		);
+	});
+}
+
+#[test]
+fn allocation_upload_rejects_tampered_tables() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let entries: [(SourceAccount, u128); 2] = [(account2.clone().into(), 100), (account3.clone().into(), 200)];
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let root = Migration::allocation_commitment(&entries);
+
+		assert_noop!(
+			Migration::commit_allocations(RuntimeOrigin::signed(account2.clone()), root, 2),
+			Error::<TestSuite>::Unauthorised
+		);
+		assert_ok!(Migration::commit_allocations(RuntimeOrigin::signed(signer.clone()), root, 2));
+		assert_noop!(
+			Migration::commit_allocations(RuntimeOrigin::signed(signer.clone()), root, 2),
+			Error::<TestSuite>::AllocationsAlreadyCommitted
+		);
+
+		// An inflated amount uploads fine but does not match the commitment.
+		assert_ok!(Migration::upload_allocations(
+			RuntimeOrigin::signed(signer.clone()),
+			allocation_chunk(&[(account2.clone().into(), 100), (account3.clone().into(), 900)])
+		));
+		assert_noop!(
+			Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries[..1])),
+			Error::<TestSuite>::TooManyAllocations
+		);
+		assert_noop!(
+			Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())),
+			Error::<TestSuite>::AllocationCommitmentMismatch
+		);
+		assert!(!Migration::allocations_sealed());
+	});
+}
+
+#[test]
+fn admin_recommits_allocations_before_sealing() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let entries: [(SourceAccount, u128); 2] = [(account2.clone().into(), 100), (account3.clone().into(), 200)];
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let root = Migration::allocation_commitment(&entries);
+
+		assert_noop!(
+			Migration::recommit_allocations(RuntimeOrigin::root(), root, 2, 0),
+			Error::<TestSuite>::AllocationsNotCommitted
+		);
+
+		// A wrong root can never be sealed.
+		assert_ok!(Migration::commit_allocations(RuntimeOrigin::signed(signer.clone()), H256::repeat_byte(1), 2));
+		assert_ok!(Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries)));
+		assert_noop!(
+			Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())),
+			Error::<TestSuite>::AllocationCommitmentMismatch
+		);
+
+		assert_noop!(
+			Migration::recommit_allocations(RuntimeOrigin::signed(signer.clone()), root, 2, 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Migration::recommit_allocations(RuntimeOrigin::root(), root, 2, 1),
+			Error::<TestSuite>::UploadedAllocationsUnderstated
+		);
+		assert_ok!(Migration::recommit_allocations(RuntimeOrigin::root(), root, 2, 2));
+		System::assert_last_event(Event::AllocationsCommitted { root, entries: 2 }.into());
+		assert_eq!(Migration::allocation(SourceAccount::from(account2.clone())), None);
+		assert_eq!(Migration::allocation_upload_progress().uploaded, 0);
+
+		// The same table uploads again and now matches.
+		assert_ok!(Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries)));
+		assert_ok!(Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())));
+		assert_noop!(
+			Migration::recommit_allocations(RuntimeOrigin::root(), root, 2, 2),
+			Error::<TestSuite>::AllocationsAlreadySealed
+		);
+	});
+}
+
+#[test]
+fn allocations_tell_source_chains_apart() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let ethereum_source = SourceAccount::Ethereum(H160::repeat_byte(1));
+	let substrate_source = SourceAccount::Substrate([1; 32]);
+	let entries: [(SourceAccount, u128); 1] = [(ethereum_source.clone(), 100)];
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(Migration::commit_allocations(RuntimeOrigin::signed(signer.clone()), Migration::allocation_commitment(&entries), 1));
+		assert_ok!(Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries)));
+		assert_ok!(Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())));
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), ethereum_source.clone(), account3.clone(), 100, None, None));
+		System::assert_last_event(Event::BalanceMigrated {
+			amount: 100,
+			from_vault: migration_vault_account.clone(),
+			for_account: ethereum_source.clone(),
+			to_account: account3.clone(),
+			vault_balance_remained: 900,
+			account_balance_after: 100,
+			source_ref: None,
 		}.into());
+		assert_eq!(Migration::migrated_amount(&ethereum_source), 100);
+
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), substrate_source, account3.clone(), 1, None, None),
+			Error::<TestSuite>::NoAllocation
+		);
+	});
+}
+
+/// Charge the transaction fee for `call`, dispatch it and refund as the runtime would.
+///
+/// Returns the fee paid by `who` and whether the call succeeded.
+fn dispatch_with_fees(who: &AccountId, call: RuntimeCall) -> (u128, bool) {
+	let info = call.get_dispatch_info();
+	let len = call.encoded_size();
+	let balance_before = Balances::free_balance(who);
+
+	let pre = ChargeTransactionPayment::<TestSuite>::from(0).pre_dispatch(who, &call, &info, len).unwrap();
+	let result = call.dispatch(RuntimeOrigin::signed(who.clone()));
+	let post_info = match &result {
+		Ok(post_info) => *post_info,
+		Err(e) => e.post_info,
+	};
+	assert_ok!(ChargeTransactionPayment::<TestSuite>::post_dispatch(
+		Some(pre),
+		&info,
+		&post_info,
+		len,
+		&result.map(|_| ()).map_err(|e| e.error),
+	));
+
+	(balance_before - Balances::free_balance(who), result.is_ok())
+}
+
+#[test]
+fn migrate_is_operational_and_free_for_owner_only() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+
+	new_test_ext(users, account1.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		let call = RuntimeCall::Migration(crate::Call::migrate {
+			for_account: account2.clone().into(),
+			account_to_credit: account3.clone(),
+			amount: 50,
+			source_ref: None,
+			category: None,
+		});
+		let info = call.get_dispatch_info();
+		assert_eq!(info.class, DispatchClass::Operational);
+		// Fees are taken up front and only refunded once the call turns out to be authorised.
+		assert_eq!(info.pays_fee, Pays::Yes);
+
+		let (fee, succeeded) = dispatch_with_fees(&migration_owner_account, call.clone());
+		assert!(succeeded);
+		assert_eq!(fee, 0);
+
+		let (fee, succeeded) = dispatch_with_fees(&account2, call);
+		assert!(!succeeded);
+		assert!(fee > 0);
+	});
+}
+
+fn validate_with_extension(who: &AccountId, call: &RuntimeCall) -> sp_runtime::transaction_validity::TransactionValidity {
+	CheckMigration::<TestSuite>::new().validate(who, call, &call.get_dispatch_info(), call.encoded_size())
+}
+
+#[test]
+fn check_migration_tags_are_kept_per_instance() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account3 = users[2].clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let source_ref = b"tx-1".to_vec();
+
+	new_test_ext(users, account1.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let for_account = H160::repeat_byte(1);
+
+		let call = RuntimeCall::Migration(crate::Call::migrate {
+			for_account: SourceAccount::from(for_account),
+			account_to_credit: account3.clone(),
+			amount: 50,
+			source_ref: Some(source_ref.clone().try_into().unwrap()),
+			category: None,
+		});
+		let valid = validate_with_extension(&account1, &call).unwrap();
+		let eth_call = RuntimeCall::MigrationEth(crate::Call::migrate {
+			for_account,
+			account_to_credit: account3.clone(),
+			amount: 50,
+			source_ref: Some(source_ref.clone().try_into().unwrap()),
+			category: None,
+		});
+		let eth_valid = CheckMigration::<TestSuite, Instance1>::new()
+			.validate(&eth_migration_owner_account(), &eth_call, &eth_call.get_dispatch_info(), eth_call.encoded_size())
+			.unwrap();
+
+		assert_eq!(valid.provides, vec![("Migration", (MigrationPalletId::get(), SourceAccount::from(for_account), source_ref.clone())).encode()]);
+		assert_eq!(eth_valid.provides, vec![("Migration", (EthMigrationPalletId::get(), for_account, source_ref)).encode()]);
+	});
+}
+
+#[test]
+fn check_migration_filters_invalid_migrations_from_the_pool() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let source_ref: SourceRef<TestSuite> = b"tx-1".to_vec().try_into().unwrap();
+
+	new_test_ext(users, account1.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		let migrate = |amount: u128| RuntimeCall::Migration(crate::Call::migrate {
+			for_account: account2.clone().into(),
+			account_to_credit: account3.clone(),
+			amount,
+			source_ref: Some(source_ref.clone()),
+			category: None,
+		});
+
+		let valid = validate_with_extension(&migration_owner_account, &migrate(50)).unwrap();
+		assert_eq!(valid.priority, u64::MAX / 2);
+		assert_eq!(valid.longevity, 64);
+		assert_eq!(valid.provides.len(), 1);
+		// Resubmitting the same migration with another amount still clashes in the pool.
+		assert_eq!(validate_with_extension(&migration_owner_account, &migrate(60)).unwrap().provides, valid.provides);
+		// Migrations without a `source_ref` can't be told apart, so none replaces another.
+		let without_ref = RuntimeCall::Migration(crate::Call::migrate {
+			for_account: account2.clone().into(),
+			account_to_credit: account3.clone(),
+			amount: 50,
+			source_ref: None,
+			category: None,
+		});
+		assert!(validate_with_extension(&migration_owner_account, &without_ref).unwrap().provides.is_empty());
+
+		assert_eq!(
+			validate_with_extension(&account2, &migrate(50)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadSigner))
+		);
+
+		// Other calls are left alone.
+		let transfer = RuntimeCall::Balances(pallet_balances::Call::transfer { dest: account3.clone().into(), value: 1 });
+		assert_eq!(validate_with_extension(&account2, &transfer).unwrap().priority, 0);
+
+		assert_ok!(migrate(50).dispatch(RuntimeOrigin::signed(migration_owner_account.clone())));
+		assert_eq!(
+			validate_with_extension(&migration_owner_account, &migrate(50)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+		assert_eq!(
+			CheckMigration::<TestSuite>::new().pre_dispatch(&migration_owner_account, &migrate(50), &migrate(50).get_dispatch_info(), 0),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		);
+	});
+}
+
+#[test]
+fn check_migration_enforces_sealed_allocations() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let entries: [(SourceAccount, u128); 1] = [(account2.clone().into(), 100)];
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		let migrate = |for_account: &AccountId, amount: u128| RuntimeCall::Migration(crate::Call::migrate {
+			for_account: for_account.clone().into(),
+			account_to_credit: account3.clone(),
+			amount,
+			source_ref: None,
+			category: None,
+		});
+
+		assert_ok!(Migration::commit_allocations(RuntimeOrigin::signed(signer.clone()), Migration::allocation_commitment(&entries), 1));
+		assert_eq!(
+			validate_with_extension(&signer, &migrate(&account2, 50)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(ALLOCATIONS_NOT_SEALED)))
+		);
+
+		assert_ok!(Migration::upload_allocations(RuntimeOrigin::signed(signer.clone()), allocation_chunk(&entries)));
+		assert_ok!(Migration::seal_allocations(RuntimeOrigin::signed(signer.clone())));
+
+		assert_ok!(validate_with_extension(&signer, &migrate(&account2, 100)));
+		assert_eq!(
+			validate_with_extension(&signer, &migrate(&account2, 101)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(ALLOCATION_EXCEEDED)))
+		);
+		assert_eq!(
+			validate_with_extension(&signer, &migrate(&account3, 1)),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(NO_ALLOCATION)))
+		);
+	});
+}
+
+#[test]
+fn migration_instances_are_independent() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+	let eth_owner = eth_migration_owner_account();
+	let source = H160::repeat_byte(7);
+	let source_ref: SourceRef<TestSuite, Instance1> = b"0xburn".to_vec().try_into().unwrap();
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		// Each instance only accepts its own owner.
+		assert_noop!(
+			MigrationEth::migrate(RuntimeOrigin::signed(migration_owner_account.clone()), source, account3.clone(), 50, None, None),
+			Error::<TestSuite, Instance1>::Unauthorised
+		);
+
+		assert_ok!(MigrationEth::migrate(RuntimeOrigin::signed(eth_owner.clone()), source, account3.clone(), 50, Some(source_ref.clone()), None));
+		System::assert_last_event(crate::Event::<TestSuite, Instance1>::BalanceMigrated {
+			amount: 50,
+			from_vault: eth_migration_vault_account(),
+			for_account: source,
+			to_account: account3.clone(),
+			vault_balance_remained: 950,
+			account_balance_after: 50,
+			source_ref: Some(source_ref.clone()),
+		}.into());
+
+		// Only the asset of the second instance moved.
+		assert_eq!(Assets::balance(ETH_TOKEN_ID, &account3), 50);
+		assert_eq!(Assets::balance(Migration::get_token_id().unwrap(), &account3), 0);
+		assert_eq!(Assets::balance(Migration::get_token_id().unwrap(), &migration_vault_account), 1000);
+
+		// Source references are tracked per instance.
+		let default_source_ref: SourceRef<TestSuite> = source_ref.to_vec().try_into().unwrap();
+		assert_eq!(Migration::processed_source_ref(&default_source_ref), None);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(migration_owner_account.clone()), account3.clone().into(), account3.clone(), 50, Some(default_source_ref), None));
+
+		assert_ne!(Migration::offchain_queue_key(), MigrationEth::offchain_queue_key());
+	});
+}
+
+#[test]
+fn transfer_lock_freezes_migrated_tokens_until_lifted() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let migration_owner_account = account1.clone();
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), migration_owner_account.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+
+		assert_noop!(
+			Migration::set_transfer_lock(RuntimeOrigin::signed(signer.clone()), Some(10)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Migration::set_transfer_lock(RuntimeOrigin::root(), Some(10)));
+		System::assert_last_event(Event::TransferLockSet { until: 10 }.into());
+		assert!(Migration::transfers_locked());
+
+		// Migrations out of the vault keep working, but the credited tokens cannot move.
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account3.clone().into(), account3.clone(), 100, None, None));
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 10),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+
+		// Other assets are not affected.
+		assert_ok!(MigrationEth::migrate(RuntimeOrigin::signed(eth_migration_owner_account()), H160::repeat_byte(1), account3.clone(), 100, None, None));
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), ETH_TOKEN_ID.into(), account2.clone().into(), 10));
+
+		System::set_block_number(9);
+		Migration::on_initialize(9);
+		assert!(Migration::transfers_locked());
+
+		System::set_block_number(10);
+		Migration::on_initialize(10);
+		System::assert_last_event(Event::TransferLockLifted.into());
+		assert_eq!(Migration::transfer_lock_until(), None);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 10));
+		assert_eq!(Assets::balance(token_id, &account2), 10);
+	});
+}
+
+#[test]
+fn fee_rate_converts_fees_to_the_migrated_asset() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		assert!(matches!(Migration::to_asset_balance(100, token_id), Err(Error::<TestSuite>::FeeRateNotSet)));
+
+		assert_noop!(
+			Migration::set_fee_rate(RuntimeOrigin::signed(signer.clone()), Some(FixedU128::from_rational(3, 2))),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Migration::set_fee_rate(RuntimeOrigin::root(), Some(FixedU128::from_rational(3, 2))));
+		System::assert_last_event(Event::FeeRateSet { rate: Some(FixedU128::from_rational(3, 2)) }.into());
+
+		assert_eq!(Migration::to_asset_balance(100, token_id).ok(), Some(150));
+		assert!(matches!(Migration::to_asset_balance(100, ETH_TOKEN_ID), Err(Error::<TestSuite>::NotMigratedAsset)));
+
+		assert_ok!(Migration::set_fee_rate(RuntimeOrigin::root(), None));
+		assert!(matches!(Migration::to_asset_balance(100, token_id), Err(Error::<TestSuite>::FeeRateNotSet)));
+	});
+}
+
+fn sponsored_pallets(pallets: Vec<u8>) -> SponsoredPallets<TestSuite> {
+	pallets.try_into().unwrap()
+}
+
+fn can_pay_fees(who: &AccountId, call: &RuntimeCall) -> bool {
+	ChargeTransactionPayment::<TestSuite>::from(0)
+		.pre_dispatch(who, call, &call.get_dispatch_info(), call.encoded_size())
+		.is_ok()
+}
+
+#[test]
+fn sponsor_pays_fees_of_migration_recipients() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let sponsor = users[1].clone();
+	let account3 = users[2].clone();
+	let recipient = get_account_id_from_seed::<sr25519::Public>("Recipient");
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, account1.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let cap = 1 << 40;
+		let pallets = sponsored_pallets(vec![<Assets as PalletInfoAccess>::index() as u8]);
+
+		assert_noop!(
+			Migration::sponsor(RuntimeOrigin::signed(sponsor.clone()), recipient.clone(), cap, pallets.clone()),
+			Error::<TestSuite>::NotSponsor
+		);
+		assert_ok!(Migration::add_sponsor(RuntimeOrigin::root(), sponsor.clone()));
+		assert_noop!(
+			Migration::sponsor(RuntimeOrigin::signed(sponsor.clone()), recipient.clone(), cap, pallets.clone()),
+			Error::<TestSuite>::NotMigrationRecipient
+		);
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(account1.clone()), account3.clone().into(), recipient.clone(), 100, None, None));
+		assert_ok!(Migration::sponsor(RuntimeOrigin::signed(sponsor.clone()), recipient.clone(), cap, pallets));
+		System::assert_last_event(Event::Sponsored { sponsor: sponsor.clone(), who: recipient.clone(), cap }.into());
+
+		// The recipient has no GAS, the sponsor pays.
+		let transfer = RuntimeCall::Assets(pallet_assets::Call::transfer {
+			id: token_id.into(),
+			target: account3.clone().into(),
+			amount: 10,
+		});
+		let sponsor_before = Balances::free_balance(&sponsor);
+		let (fee, succeeded) = dispatch_with_fees(&recipient, transfer.clone());
+		assert!(succeeded);
+		assert_eq!(fee, 0);
+		assert_eq!(Assets::balance(token_id, &account3), 10);
+
+		let paid = sponsor_before - Balances::free_balance(&sponsor);
+		assert!(paid > 0);
+		assert_eq!(Migration::sponsorship(&recipient).unwrap().remaining, cap - paid);
+
+		// Tips are not sponsored, so they cannot drain the cap.
+		assert!(ChargeTransactionPayment::<TestSuite>::from(1 << 30)
+			.pre_dispatch(&recipient, &transfer, &transfer.get_dispatch_info(), transfer.encoded_size())
+			.is_err());
+		assert_eq!(Migration::sponsorship(&recipient).unwrap().remaining, cap - paid);
+		assert_eq!(Balances::free_balance(&sponsor), sponsor_before - paid);
+
+		// Calls to other pallets are not sponsored.
+		assert!(!can_pay_fees(&recipient, &RuntimeCall::System(frame_system::Call::remark { remark: vec![] })));
+
+		// Neither are calls once the sponsor is removed.
+		assert_ok!(Migration::remove_sponsor(RuntimeOrigin::root(), sponsor.clone()));
+		assert!(!can_pay_fees(&recipient, &transfer));
+	});
+}
+
+#[test]
+fn sponsorships_are_capped_and_owned_by_their_sponsor() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let sponsor = users[1].clone();
+	let other_sponsor = users[2].clone();
+	let recipient = get_account_id_from_seed::<sr25519::Public>("Recipient");
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, account1.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let pallets = sponsored_pallets(vec![<Assets as PalletInfoAccess>::index() as u8]);
+		let transfer = RuntimeCall::Assets(pallet_assets::Call::transfer {
+			id: token_id.into(),
+			target: account1.clone().into(),
+			amount: 10,
+		});
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(account1.clone()), account1.clone().into(), recipient.clone(), 100, None, None));
+		assert_ok!(Migration::add_sponsor(RuntimeOrigin::root(), sponsor.clone()));
+		assert_ok!(Migration::add_sponsor(RuntimeOrigin::root(), other_sponsor.clone()));
+
+		// A cap below the fee doesn't cover the call.
+		assert_ok!(Migration::sponsor(RuntimeOrigin::signed(sponsor.clone()), recipient.clone(), 1, pallets.clone()));
+		assert!(!can_pay_fees(&recipient, &transfer));
+
+		assert_noop!(
+			Migration::sponsor(RuntimeOrigin::signed(other_sponsor.clone()), recipient.clone(), 1 << 40, pallets.clone()),
+			Error::<TestSuite>::SponsoredByOther
+		);
+		assert_noop!(
+			Migration::unsponsor(RuntimeOrigin::signed(other_sponsor.clone()), recipient.clone()),
+			Error::<TestSuite>::SponsoredByOther
+		);
+
+		assert_ok!(Migration::sponsor(RuntimeOrigin::signed(sponsor.clone()), recipient.clone(), 1 << 40, pallets));
+		assert!(can_pay_fees(&recipient, &transfer));
+
+		assert_ok!(Migration::unsponsor(RuntimeOrigin::signed(sponsor.clone()), recipient.clone()));
+		assert_eq!(Migration::sponsorship(&recipient), None);
+		assert!(!can_pay_fees(&recipient, &transfer));
+	});
+}
+
+fn storage_proof(nodes: Vec<Vec<u8>>) -> StorageProofOf<TestSuite> {
+	nodes.try_into().unwrap()
+}
+
+#[test]
+fn balances_proven_against_source_state_root_can_be_claimed_once() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let holder: SourceAccount = account2.clone().into();
+		let other_holder: SourceAccount = account3.clone().into();
+		let (root, proof) = build_source_state(&[
+			(Migration::source_balance_key(&holder), source_account_info(300)),
+			(Migration::source_balance_key(&other_holder), source_account_info(50)),
+		]);
+
+		assert_noop!(
+			Migration::set_source_state_root(RuntimeOrigin::signed(signer.clone()), 100, Some(root)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 100, holder.clone(), account2.clone(), storage_proof(proof.clone())),
+			Error::<TestSuite>::UnknownSourceStateRoot
+		);
+		assert_ok!(Migration::set_source_state_root(RuntimeOrigin::root(), 100, Some(root)));
+
+		// Only the operator submits claims, but the amount comes from the proof.
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(account2.clone()), 100, holder.clone(), account2.clone(), storage_proof(proof.clone())),
+			Error::<TestSuite>::Unauthorised
+		);
+		assert_ok!(Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 100, holder.clone(), account2.clone(), storage_proof(proof.clone())));
+		System::assert_last_event(Event::StorageProofClaimed { block: 100, for_account: holder.clone(), amount: 300 }.into());
+		assert_eq!(Assets::balance(Migration::get_token_id().unwrap(), &account2), 300);
+		assert_eq!(Migration::proven_claim(&holder), Some(100));
+
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 100, holder, account2.clone(), storage_proof(proof)),
+			Error::<TestSuite>::AlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn invalid_storage_proofs_are_rejected() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let holder: SourceAccount = account2.clone().into();
+		let (root, proof) = build_source_state(&[(Migration::source_balance_key(&holder), source_account_info(300))]);
+		assert_ok!(Migration::set_source_state_root(RuntimeOrigin::root(), 100, Some(root)));
+
+		// A proof of another state, e.g. with an inflated balance.
+		let (_, forged) = build_source_state(&[(Migration::source_balance_key(&holder), source_account_info(900))]);
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 100, holder.clone(), account2.clone(), storage_proof(forged)),
+			Error::<TestSuite>::InvalidStorageProof
+		);
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 100, holder.clone(), account2.clone(), storage_proof(vec![])),
+			Error::<TestSuite>::InvalidStorageProof
+		);
+
+		// The state has no balance for this account.
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 100, account3.clone().into(), account3.clone(), storage_proof(proof)),
+			Error::<TestSuite>::NoProvenBalance
+		);
+		assert_eq!(Migration::proven_claim(&holder), None);
+	});
+}
+
+fn grandpa_pairs(seeds: &[u8]) -> Vec<ed25519::Pair> {
+	seeds.iter().map(|seed| ed25519::Pair::from_seed(&[*seed; 32])).collect()
+}
+
+fn grandpa_authorities(pairs: &[ed25519::Pair]) -> Vec<(GrandpaId, u64)> {
+	pairs.iter().map(|pair| (pair.public().into(), 1)).collect()
+}
+
+fn source_header(number: u32, parent_hash: H256, state_root: H256) -> SourceHeader {
+	SourceHeader::new(number, Default::default(), state_root, parent_hash, Default::default())
+}
+
+/// Encoded justification of `header` signed by `signers`.
+fn justify(header: &SourceHeader, set_id: u64, signers: &[ed25519::Pair]) -> Vec<u8> {
+	let voters = signers.iter().map(|pair| pair.public().into()).collect::<Vec<GrandpaId>>();
+	build_justification(header, 1, set_id, &voters, |id, payload| {
+		let pair = signers.iter().find(|pair| GrandpaId::from(pair.public()) == *id).unwrap();
+		AuthoritySignature::from(pair.sign(payload))
+	})
+	.encode()
+}
+
+#[test]
+fn light_client_finalizes_headers_and_follows_authority_set_changes() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let authorities = grandpa_pairs(&[1, 2, 3, 4]);
+		let checkpoint = source_header(10, H256::repeat_byte(9), H256::repeat_byte(10));
+		let header = source_header(11, checkpoint.hash(), H256::repeat_byte(11));
+
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 0, &authorities)),
+			Error::<TestSuite>::LightClientNotInitialized
+		);
+		assert_noop!(
+			Migration::initialize_light_client(RuntimeOrigin::signed(signer.clone()), checkpoint.clone(), 0, grandpa_authorities(&authorities).try_into().unwrap()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Migration::initialize_light_client(RuntimeOrigin::root(), checkpoint.clone(), 0, grandpa_authorities(&authorities).try_into().unwrap()));
+		assert_eq!(Migration::best_finalized_source_header(), Some((10, checkpoint.hash())));
+		assert_eq!(Migration::finalized_source_root(10), Some(H256::repeat_byte(10)));
+
+		// Three of four authorities are needed, and only those of the current set count.
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 0, &authorities[..2])),
+			Error::<TestSuite>::InvalidJustification
+		);
+		let outsiders = grandpa_pairs(&[5, 6]);
+		let mixed = vec![authorities[0].clone(), outsiders[0].clone(), outsiders[1].clone()];
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 0, &mixed)),
+			Error::<TestSuite>::InvalidJustification
+		);
+		let twice = vec![authorities[0].clone(), authorities[1].clone(), authorities[1].clone()];
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 0, &twice)),
+			Error::<TestSuite>::InvalidJustification
+		);
+		// Signatures of another set or header do not count.
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 1, &authorities[..3])),
+			Error::<TestSuite>::InvalidJustification
+		);
+		let forged = source_header(11, checkpoint.hash(), H256::repeat_byte(66));
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), forged, justify(&header, 0, &authorities[..3])),
+			Error::<TestSuite>::InvalidJustification
+		);
+
+		assert_ok!(Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 0, &authorities[..3])));
+		System::assert_last_event(Event::SourceHeaderFinalized { number: 11, hash: header.hash() }.into());
+		assert_eq!(Migration::finalized_source_root(11), Some(H256::repeat_byte(11)));
+		// Finalized roots are not snapshots balances can be claimed at.
+		assert_eq!(Migration::source_state_root(11), None);
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), header.clone(), justify(&header, 0, &authorities)),
+			Error::<TestSuite>::OldSourceHeader
+		);
+
+		// The header enacting a new set is finalized by the old one.
+		let next_authorities = grandpa_pairs(&[7, 8]);
+		let mut change = source_header(12, header.hash(), H256::repeat_byte(12));
+		let log = ConsensusLog::<u32>::ScheduledChange(ScheduledChange {
+			next_authorities: grandpa_authorities(&next_authorities),
+			delay: 0,
+		});
+		change.digest.push(DigestItem::Consensus(GRANDPA_ENGINE_ID, log.encode()));
+		assert_ok!(Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), change.clone(), justify(&change, 0, &authorities[..3])));
+		System::assert_has_event(Event::SourceAuthoritySetChanged { set_id: 1 }.into());
+		assert_eq!(Migration::source_authority_set_id(), 1);
+		assert_eq!(Migration::source_authorities().into_inner(), grandpa_authorities(&next_authorities));
+
+		let next = source_header(13, change.hash(), H256::repeat_byte(13));
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), next.clone(), justify(&next, 0, &authorities)),
+			Error::<TestSuite>::InvalidJustification
+		);
+		assert_ok!(Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), next.clone(), justify(&next, 1, &next_authorities)));
+		assert_eq!(Migration::best_finalized_source_header(), Some((13, next.hash())));
+
+		// Delayed changes cannot be followed.
+		let mut delayed = source_header(14, next.hash(), H256::repeat_byte(14));
+		let log = ConsensusLog::<u32>::ScheduledChange(ScheduledChange { next_authorities: grandpa_authorities(&authorities), delay: 5 });
+		delayed.digest.push(DigestItem::Consensus(GRANDPA_ENGINE_ID, log.encode()));
+		assert_noop!(
+			Migration::submit_finality_proof(RuntimeOrigin::signed(signer.clone()), delayed.clone(), justify(&delayed, 1, &next_authorities)),
+			Error::<TestSuite>::UnsupportedAuthoritySetChange
+		);
+	});
+}
+
+#[test]
+fn burns_proven_on_finalized_headers_are_credited_once() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let holder: SourceAccount = account2.clone().into();
+		let (root, proof) = build_source_state(&[source_events_entry(vec![
+			SourceEvent::Transferred { from: holder.clone(), to: account3.clone().into(), amount: 10 }.encode(),
+			SourceEvent::Burned { who: holder.clone(), amount: 250, to: account2.clone() }.encode(),
+		])]);
+
+		let authorities = grandpa_pairs(&[1, 2, 3]);
+		let checkpoint = source_header(10, H256::repeat_byte(9), H256::repeat_byte(10));
+		assert_ok!(Migration::initialize_light_client(RuntimeOrigin::root(), checkpoint.clone(), 0, grandpa_authorities(&authorities).try_into().unwrap()));
+		assert_noop!(
+			Migration::claim_burn(RuntimeOrigin::signed(account3.clone()), 11, 1, storage_proof(proof.clone())),
+			Error::<TestSuite>::UnknownSourceStateRoot
+		);
+		let header = source_header(11, checkpoint.hash(), root);
+		assert_ok!(Migration::submit_finality_proof(RuntimeOrigin::signed(account3.clone()), header.clone(), justify(&header, 0, &authorities)));
+		assert_noop!(
+			Migration::claim_with_storage_proof(RuntimeOrigin::signed(signer.clone()), 11, holder.clone(), account2.clone(), storage_proof(proof.clone())),
+			Error::<TestSuite>::UnknownSourceStateRoot
+		);
+
+		assert_noop!(
+			Migration::claim_burn(RuntimeOrigin::signed(account3.clone()), 11, 0, storage_proof(proof.clone())),
+			Error::<TestSuite>::NotABurn
+		);
+		assert_noop!(
+			Migration::claim_burn(RuntimeOrigin::signed(account3.clone()), 11, 2, storage_proof(proof.clone())),
+			Error::<TestSuite>::NoSuchSourceEvent
+		);
+
+		// Anyone can relay the burn, the tokens go to the account named in it.
+		assert_ok!(Migration::claim_burn(RuntimeOrigin::signed(account3.clone()), 11, 1, storage_proof(proof.clone())));
+		System::assert_last_event(Event::BurnClaimed { block: 11, event_index: 1, for_account: holder, amount: 250 }.into());
+		assert_eq!(Assets::balance(Migration::get_token_id().unwrap(), &account2), 250);
+		assert_noop!(
+			Migration::claim_burn(RuntimeOrigin::signed(account3.clone()), 11, 1, storage_proof(proof)),
+			Error::<TestSuite>::BurnAlreadyClaimed
+		);
+	});
+}
+
+fn eth_storage_proof(nodes: Vec<Vec<u8>>) -> StorageProofOf<TestSuite, Instance1> {
+	nodes.try_into().unwrap()
+}
+
+fn eth_receipt(receipt: Vec<u8>) -> ReceiptOf<TestSuite, Instance1> {
+	receipt.try_into().unwrap()
+}
+
+#[test]
+fn relayers_manage_trusted_ethereum_blocks() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let relayer = users[1].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let block_hash = H256::repeat_byte(1);
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			MigrationEth::set_trusted_ethereum_block(RuntimeOrigin::signed(relayer.clone()), block_hash, true),
+			Error::<TestSuite, Instance1>::NotRelayer
+		);
+		assert_noop!(MigrationEth::add_relayer(RuntimeOrigin::signed(signer.clone()), relayer.clone()), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(MigrationEth::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+
+		assert_ok!(MigrationEth::set_trusted_ethereum_block(RuntimeOrigin::signed(relayer.clone()), block_hash, true));
+		System::assert_last_event(crate::Event::<TestSuite, Instance1>::EthereumBlockTrusted { block_hash, trusted: true }.into());
+		assert!(crate::TrustedEthereumBlocks::<TestSuite, Instance1>::contains_key(block_hash));
+		assert!(!crate::TrustedEthereumBlocks::<TestSuite>::contains_key(block_hash));
+
+		// A block that turns out to be reorganised away is dropped again.
+		assert_ok!(MigrationEth::set_trusted_ethereum_block(RuntimeOrigin::signed(relayer.clone()), block_hash, false));
+		assert!(!crate::TrustedEthereumBlocks::<TestSuite, Instance1>::contains_key(block_hash));
+
+		assert_ok!(MigrationEth::remove_relayer(RuntimeOrigin::root(), relayer.clone()));
+		assert_noop!(MigrationEth::remove_relayer(RuntimeOrigin::root(), relayer.clone()), Error::<TestSuite, Instance1>::NotRelayer);
+		assert_noop!(
+			MigrationEth::set_trusted_ethereum_block(RuntimeOrigin::signed(relayer.clone()), block_hash, true),
+			Error::<TestSuite, Instance1>::NotRelayer
+		);
+	});
+}
+
+#[test]
+fn erc20_burns_proven_by_receipts_are_credited_once() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let holder = H160::repeat_byte(7);
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token = Erc20Token::address();
+		let burn_address = MigrationEth::erc20_burn_address(&account2);
+		assert_ne!(burn_address, MigrationEth::erc20_burn_address(&account3));
+		assert_ne!(burn_address, Migration::erc20_burn_address(&account2));
+
+		let receipts = vec![
+			// A plain transfer, a burn of another token and the burn for `account2`.
+			erc20_burn_receipt(token, holder, H160::repeat_byte(9), 40),
+			erc20_burn_receipt(H160::repeat_byte(0xee), holder, burn_address, 500),
+			erc20_burn_receipt(token, holder, burn_address, 300),
+		];
+		let (receipts_root, proof) = build_receipts_trie(&receipts);
+		let header = ethereum_header(17_000_000, receipts_root);
+		let block_hash = H256(sp_io::hashing::keccak_256(&header));
+
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 2, eth_receipt(receipts[2].clone()), eth_storage_proof(proof.clone()), account2.clone()),
+			Error::<TestSuite, Instance1>::UntrustedEthereumBlock
+		);
+		assert_ok!(MigrationEth::add_relayer(RuntimeOrigin::root(), account1.clone()));
+		assert_ok!(MigrationEth::set_trusted_ethereum_block(RuntimeOrigin::signed(account1.clone()), block_hash, true));
+
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 0, eth_receipt(receipts[0].clone()), eth_storage_proof(proof.clone()), account2.clone()),
+			Error::<TestSuite, Instance1>::NoBurnInReceipt
+		);
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 1, eth_receipt(receipts[1].clone()), eth_storage_proof(proof.clone()), account2.clone()),
+			Error::<TestSuite, Instance1>::NoBurnInReceipt
+		);
+		// The burn only credits the account its burn address belongs to.
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 2, eth_receipt(receipts[2].clone()), eth_storage_proof(proof.clone()), account3.clone()),
+			Error::<TestSuite, Instance1>::NoBurnInReceipt
+		);
+		// Receipts that are not in the block are rejected.
+		let forged = erc20_burn_receipt(token, holder, burn_address, 900);
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 2, eth_receipt(forged.clone()), eth_storage_proof(proof.clone()), account2.clone()),
+			Error::<TestSuite, Instance1>::InvalidReceiptProof
+		);
+		let (_, forged_proof) = build_receipts_trie(&[receipts[0].clone(), receipts[1].clone(), forged.clone()]);
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 2, eth_receipt(forged), eth_storage_proof(forged_proof), account2.clone()),
+			Error::<TestSuite, Instance1>::InvalidReceiptProof
+		);
+
+		// Anyone can submit the claim.
+		assert_ok!(MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 2, eth_receipt(receipts[2].clone()), eth_storage_proof(proof.clone()), account2.clone()));
+		System::assert_last_event(crate::Event::<TestSuite, Instance1>::Erc20BurnClaimed { block_hash, tx_index: 2, for_account: holder, amount: 300 }.into());
+		assert_eq!(Assets::balance(ETH_TOKEN_ID, &account2), 300);
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header, 2, eth_receipt(receipts[2].clone()), eth_storage_proof(proof), account2.clone()),
+			Error::<TestSuite, Instance1>::ReceiptAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn all_burns_of_a_receipt_are_credited_together() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let holder = H160::repeat_byte(7);
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token = Erc20Token::address();
+		let burn_address = MigrationEth::erc20_burn_address(&account2);
+		let receipts = vec![
+			erc20_burns_receipt(token, &[(holder, 100), (holder, 250)], burn_address),
+			erc20_burns_receipt(token, &[(holder, 100), (H160::repeat_byte(8), 250)], burn_address),
+		];
+		let (receipts_root, proof) = build_receipts_trie(&receipts);
+		let header = ethereum_header(17_000_000, receipts_root);
+		let block_hash = H256(sp_io::hashing::keccak_256(&header));
+		assert_ok!(MigrationEth::add_relayer(RuntimeOrigin::root(), account1.clone()));
+		assert_ok!(MigrationEth::set_trusted_ethereum_block(RuntimeOrigin::signed(account1.clone()), block_hash, true));
+
+		// Burns of several holders can't be credited to one source account.
+		assert_noop!(
+			MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header.clone(), 1, eth_receipt(receipts[1].clone()), eth_storage_proof(proof.clone()), account2.clone()),
+			Error::<TestSuite, Instance1>::NoBurnInReceipt
+		);
+
+		assert_ok!(MigrationEth::claim_erc20_burn(RuntimeOrigin::signed(account3.clone()), header, 0, eth_receipt(receipts[0].clone()), eth_storage_proof(proof), account2.clone()));
+		System::assert_last_event(crate::Event::<TestSuite, Instance1>::Erc20BurnClaimed { block_hash, tx_index: 0, for_account: holder, amount: 350 }.into());
+		assert_eq!(Assets::balance(ETH_TOKEN_ID, &account2), 350);
+
+		// Receipts are bounded, and the call is weighed by their length.
+		assert!(ReceiptOf::<TestSuite, Instance1>::try_from(vec![0u8; 16_385]).is_err());
+		let weight = |length: usize| crate::Call::<TestSuite, Instance1>::claim_erc20_burn {
+			header: vec![],
+			tx_index: 0,
+			receipt: eth_receipt(vec![0; length]),
+			proof: eth_storage_proof(vec![]),
+			account_to_credit: account2.clone(),
+		}
+		.get_dispatch_info()
+		.weight;
+		assert_eq!(weight(100), <() as crate::WeightInfo>::claim_erc20_burn(0, 100));
+		assert!(weight(16_384).ref_time() > weight(100).ref_time());
+	});
+}
+
+#[test]
+fn receipts_trie_proofs_follow_extensions_and_branches() {
+	// Enough receipts for keys of different lengths, with extension and embedded nodes.
+	let receipts = (0..130u32).map(|index| index.encode()).collect::<Vec<_>>();
+	let (root, proof) = build_receipts_trie(&receipts);
+	for index in [0u32, 1, 15, 16, 127, 128, 129] {
+		let key = rlp::encode(&index);
+		assert_eq!(Migration::read_ethereum_trie(&root, &key, &proof), Some(index.encode()));
+	}
+	assert_eq!(Migration::read_ethereum_trie(&root, &rlp::encode(&130u32), &proof), None);
+	assert_eq!(Migration::read_ethereum_trie(&H256::repeat_byte(1), &rlp::encode(&0u32), &proof), None);
+}
+
+fn attester_pairs(seeds: &[u8]) -> Vec<sr25519::Pair> {
+	seeds.iter().map(|seed| sr25519::Pair::from_seed(&[*seed; 32])).collect()
+}
+
+fn attesters(pairs: &[sr25519::Pair]) -> AttestersOf<TestSuite> {
+	pairs.iter().map(|pair| pair.public().into()).collect::<Vec<crate::crypto::Public>>().try_into().unwrap()
+}
+
+/// Signatures of `payload` by the attesters at `indices` of `pairs`.
+fn attest(pairs: &[sr25519::Pair], indices: &[u32], payload: &[u8]) -> AttestationsOf<TestSuite> {
+	indices
+		.iter()
+		.map(|index| (*index, crate::crypto::Signature::from(pairs[*index as usize].sign(payload))))
+		.collect::<Vec<_>>()
+		.try_into()
+		.unwrap()
+}
+
+#[test]
+fn attester_sets_are_governed_and_versioned() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let pairs = attester_pairs(&[1, 2, 3]);
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(Migration::set_attesters(RuntimeOrigin::signed(signer.clone()), attesters(&pairs), 2), sp_runtime::DispatchError::BadOrigin);
+		assert_noop!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs), 0), Error::<TestSuite>::InvalidAttesterThreshold);
+		assert_noop!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs), 4), Error::<TestSuite>::InvalidAttesterThreshold);
+		let duplicated = vec![pairs[0].clone(), pairs[1].clone(), pairs[0].clone()];
+		assert_noop!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&duplicated), 2), Error::<TestSuite>::DuplicateAttester);
+
+		assert_ok!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs), 2));
+		System::assert_last_event(Event::AttestersSet { set_id: 1, threshold: 2 }.into());
+		assert_ok!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs[..2]), 1));
+		assert_eq!(Migration::attester_set_id(), 2);
+		assert_eq!(Migration::attester_threshold(), 1);
+		assert_eq!(Migration::attesters(), attesters(&pairs[..2]));
+	});
+}
+
+#[test]
+fn migrations_attested_by_the_threshold_are_executed_unsigned() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let pairs = attester_pairs(&[1, 2, 3]);
+	let source_ref: SourceRef<TestSuite> = b"0xburn".to_vec().try_into().unwrap();
+	let for_account: SourceAccount = account2.clone().into();
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let migrate = |attestations: AttestationsOf<TestSuite>| crate::Call::<TestSuite>::migrate_attested {
+			for_account: for_account.clone(),
+			account_to_credit: account2.clone(),
+			amount: 100,
+			source_ref: source_ref.clone(),
+			category: None,
+			attestations,
+		};
+		let validate = |call: &crate::Call<TestSuite>| Migration::validate_unsigned(TransactionSource::External, call);
+
+		let payload = Migration::attestation_payload(&source_ref, &for_account, &account2, 100, None);
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account2.clone(), 100, source_ref.clone(), None, attest(&pairs, &[0, 1], &payload)),
+			Error::<TestSuite>::NoAttesters
+		);
+		assert_ok!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs), 2));
+		let payload = Migration::attestation_payload(&source_ref, &for_account, &account2, 100, None);
+
+		// Below the threshold, with a signer counted twice or a signature of other terms.
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account2.clone(), 100, source_ref.clone(), None, attest(&pairs, &[2], &payload)),
+			Error::<TestSuite>::InsufficientAttestations
+		);
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account2.clone(), 100, source_ref.clone(), None, attest(&pairs, &[2, 2], &payload)),
+			Error::<TestSuite>::InvalidAttestation
+		);
+		let inflated = Migration::attestation_payload(&source_ref, &for_account, &account2, 900, None);
+		let attestations: AttestationsOf<TestSuite> = attest(&pairs, &[0], &payload)
+			.into_iter()
+			.chain(attest(&pairs, &[1], &inflated))
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account2.clone(), 100, source_ref.clone(), None, attestations.clone()),
+			Error::<TestSuite>::InvalidAttestation
+		);
+		assert_eq!(validate(&migrate(attestations)), Err(InvalidTransaction::BadProof.into()));
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::signed(signer.clone()), for_account.clone(), account2.clone(), 100, source_ref.clone(), None, attest(&pairs, &[0, 1], &payload)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		let call = migrate(attest(&pairs, &[0, 2], &payload));
+		assert!(validate(&call).is_ok());
+		assert_ok!(RuntimeCall::Migration(call.clone()).dispatch(RuntimeOrigin::none()));
+		System::assert_last_event(Event::MigrationAttested { set_id: 1, source_ref: source_ref.clone() }.into());
+		assert_eq!(Assets::balance(Migration::get_token_id().unwrap(), &account2), 100);
+
+		// Each transfer on the source chain is migrated once.
+		assert_eq!(validate(&call), Err(InvalidTransaction::Stale.into()));
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account2.clone(), 100, source_ref.clone(), None, attest(&pairs, &[0, 2], &payload)),
+			Error::<TestSuite>::SourceRefAlreadyUsed
+		);
+
+		// Signatures of a replaced set are void.
+		let other_ref: SourceRef<TestSuite> = b"0xother".to_vec().try_into().unwrap();
+		let old_payload = Migration::attestation_payload(&other_ref, &for_account, &account2, 100, None);
+		assert_ok!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs), 2));
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account2.clone(), 100, other_ref, None, attest(&pairs, &[0, 1], &old_payload)),
+			Error::<TestSuite>::InvalidAttestation
+		);
+	});
+}
+
+#[test]
+fn tokens_moved_back_are_queued_for_release_up_to_the_cap() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let source = H160::repeat_byte(7);
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account2.clone(), 200, None, None));
+
+		assert_noop!(
+			Migration::migrate_back(RuntimeOrigin::signed(account2.clone()), source.into(), 50),
+			Error::<TestSuite>::OutboundDisabled
+		);
+		assert_noop!(Migration::set_outbound_cap(RuntimeOrigin::signed(signer.clone()), Some(150)), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(Migration::set_outbound_cap(RuntimeOrigin::root(), Some(150)));
+		assert_noop!(
+			Migration::migrate_back(RuntimeOrigin::signed(account2.clone()), source.into(), 0),
+			Error::<TestSuite>::NothingToRelease
+		);
+
+		assert_ok!(Migration::migrate_back(RuntimeOrigin::signed(account2.clone()), source.into(), 60));
+		System::assert_last_event(Event::MigratedBack { nonce: 0, who: account2.clone(), to: source.into(), amount: 60 }.into());
+		assert_ok!(Migration::migrate_back(RuntimeOrigin::signed(account2.clone()), account2.clone().into(), 60));
+		assert_eq!(Assets::balance(token_id, &account2), 80);
+		assert_eq!(Assets::balance(token_id, &migration_vault_account), 920);
+
+		// Pending releases are capped until the relayer confirms them.
+		assert_noop!(
+			Migration::migrate_back(RuntimeOrigin::signed(account2.clone()), source.into(), 40),
+			Error::<TestSuite>::OutboundCapExceeded
+		);
+		assert_eq!(Migration::pending_releases(), vec![
+			(0, OutboundRelease { who: account2.clone(), to: SourceAccount::from(source), amount: 60 }),
+			(1, OutboundRelease { who: account2.clone(), to: SourceAccount::from(account2.clone()), amount: 60 }),
+		]);
+
+		assert_noop!(Migration::confirm_release(RuntimeOrigin::signed(account2.clone()), 0), Error::<TestSuite>::Unauthorised);
+		assert_ok!(Migration::confirm_release(RuntimeOrigin::signed(signer.clone()), 0));
+		System::assert_last_event(Event::ReleaseConfirmed { nonce: 0 }.into());
+		assert_noop!(Migration::confirm_release(RuntimeOrigin::signed(signer.clone()), 0), Error::<TestSuite>::UnknownRelease);
+		assert_eq!(Migration::pending_outbound(), 60);
+
+		assert_ok!(Migration::migrate_back(RuntimeOrigin::signed(account2.clone()), source.into(), 40));
+		assert_eq!(Migration::pending_releases().iter().map(|(nonce, _)| *nonce).collect::<Vec<_>>(), vec![1, 2]);
+	});
+}
+
+#[test]
+fn tokens_moved_back_stay_counted_as_migrated() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let team: CategoryName<TestSuite> = b"team".to_vec().try_into().unwrap();
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team.clone(), 300, None));
+		let team_vault = Migration::category_vault(&team);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(migration_vault_account.clone()), token_id.into(), team_vault.clone().into(), 300));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 100, None, Some(team.clone())));
+
+		assert_ok!(Migration::set_outbound_cap(RuntimeOrigin::root(), Some(100)));
+		assert_ok!(Migration::migrate_back(RuntimeOrigin::signed(account3.clone()), account2.clone().into(), 60));
+		assert_eq!(Assets::balance(token_id, &account3), 40);
+		assert_eq!(Assets::balance(token_id, &migration_vault_account), 760);
+
+		// The migration records are one-way; the release is only tracked as pending.
+		assert_eq!(Migration::credited(SourceAccount::from(account2.clone()), &account3), 100);
+		assert_eq!(Migration::category_amount(&account3, &team), 100);
+		assert_eq!(Migration::category(&team).unwrap().migrated, 100);
+		assert_eq!(Migration::pending_outbound(), 60);
+	});
+}
+
+#[test]
+fn on_migrated_is_called_after_successful_migrations() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(account2.clone()), account2.clone().into(), account2.clone(), 50, None, None),
+			Error::<TestSuite>::Unauthorised
+		);
+		assert!(MigrationLog::migrated().is_empty());
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 50, None, None));
+		assert_eq!(MigrationLog::migrated(), vec![(SourceAccount::from(account2.clone()), account3.clone(), 50, token_id)]);
+
+		// The hook is charged to every call that migrates.
+		let call = crate::Call::<TestSuite>::migrate { for_account: account2.clone().into(), account_to_credit: account3.clone(), amount: 50, source_ref: None, category: None };
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			<() as crate::WeightInfo>::migrate().saturating_add(<MigrationLog as crate::OnMigrated<_, _, _, _>>::weight())
+		);
+	});
+}
+
+#[test]
+fn queued_migrations_are_executed_in_on_idle_within_the_budget() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let source_ref: SourceRef<TestSuite> = b"tx-1".to_vec().try_into().unwrap();
+		let queued = |account: &AccountId, amount| QueuedMigration {
+			for_account: SourceAccount::from(account.clone()),
+			account_to_credit: account.clone(),
+			amount,
+			source_ref: Some(source_ref.clone()),
+			category: None,
+		};
+		// The second migration reuses the source reference of the first and fails.
+		let batch: MigrationBatch<TestSuite> = vec![queued(&account2, 50), queued(&account3, 60)].try_into().unwrap();
+
+		assert_noop!(
+			Migration::enqueue_migrations(RuntimeOrigin::signed(account2.clone()), batch.clone()),
+			Error::<TestSuite>::Unauthorised
+		);
+		let info = Migration::enqueue_migrations(RuntimeOrigin::signed(signer.clone()), batch).unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		System::assert_last_event(Event::MigrationsQueued { first: 0, count: 2 }.into());
+
+		let last = QueuedMigration { source_ref: None, ..queued(&account3, 70) };
+		assert_noop!(
+			Migration::enqueue_migrations(RuntimeOrigin::signed(signer.clone()), vec![last.clone(); 3].try_into().unwrap()),
+			Error::<TestSuite>::MigrationQueueFull
+		);
+		assert_ok!(Migration::enqueue_migrations(RuntimeOrigin::signed(signer.clone()), vec![last].try_into().unwrap()));
+		assert_eq!(Migration::migration_queue_progress(), MigrationQueueProgress { processed: 0, pending: 3 });
+
+		let base = <TestSuite as frame_system::Config>::DbWeight::get().reads(3);
+		let item = <() as crate::WeightInfo>::process_queued_migration().saturating_add(<MigrationLog as crate::OnMigrated<_, _, _, _>>::weight());
+
+		// Without room for a single migration nothing is executed.
+		assert_eq!(Migration::on_idle(1, base.saturating_add(item).saturating_sub(Weight::from_ref_time(1))), base);
+		assert_eq!(Migration::migration_queue_head(), 0);
+
+		assert_eq!(Migration::on_idle(1, base.saturating_add(item.saturating_mul(2))), base.saturating_add(item.saturating_mul(2)));
+		assert_eq!(Assets::balance(token_id, &account2), 50);
+		assert_eq!(Assets::balance(token_id, &account3), 0);
+		System::assert_last_event(Event::QueuedMigrationFailed { index: 1, error: Error::<TestSuite>::SourceRefAlreadyUsed.into() }.into());
+		assert_eq!(Migration::migration_queue_progress(), MigrationQueueProgress { processed: 2, pending: 1 });
+		assert_eq!(Migration::failed_migrations(), vec![(1, MigrationRequest {
+			for_account: SourceAccount::from(account3.clone()),
+			account_to_credit: account3.clone(),
+			amount: 60,
+			source_ref: Some(b"tx-1".to_vec()),
+			category: None,
+		})]);
+
+		assert_eq!(Migration::on_idle(2, Weight::MAX), base.saturating_add(item));
+		assert_eq!(Assets::balance(token_id, &account3), 70);
+		assert_eq!(Assets::balance(token_id, &migration_vault_account), 880);
+		assert_eq!(Migration::migration_queue_progress(), MigrationQueueProgress { processed: 3, pending: 0 });
+		assert_eq!(Migration::on_idle(3, Weight::MAX), base);
+	});
+}
+
+#[test]
+fn categories_are_migrated_from_their_sub_vaults_within_caps_and_with_vesting() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let public: CategoryName<TestSuite> = b"public".to_vec().try_into().unwrap();
+		let team: CategoryName<TestSuite> = b"team".to_vec().try_into().unwrap();
+		let vesting = CategoryVesting { start: 10, duration: 100 };
+
+		assert_noop!(
+			Migration::set_category(RuntimeOrigin::signed(signer.clone()), public.clone(), 300, None),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), public.clone(), 300, None));
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team.clone(), 200, Some(vesting)));
+		let public_vault = Migration::category_vault(&public);
+		let team_vault = Migration::category_vault(&team);
+		System::assert_last_event(Event::CategorySet { name: team.clone(), vault: team_vault.clone(), cap: 200, vesting: Some(vesting) }.into());
+		assert_noop!(
+			Migration::set_category(RuntimeOrigin::root(), b"ecosystem".to_vec().try_into().unwrap(), 100, None),
+			Error::<TestSuite>::TooManyCategories
+		);
+
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(migration_vault_account.clone()), token_id.into(), public_vault.clone().into(), 300));
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(migration_vault_account.clone()), token_id.into(), team_vault.clone().into(), 200));
+
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 50, None, Some(b"ecosystem".to_vec().try_into().unwrap())),
+			Error::<TestSuite>::UnknownCategory
+		);
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 250, None, Some(team.clone())),
+			Error::<TestSuite>::CategoryCapExceeded
+		);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 100, None, Some(team.clone())));
+		System::assert_last_event(Event::BalanceMigrated {
+			amount: 100,
+			from_vault: team_vault.clone(),
+			for_account: account2.clone().into(),
+			to_account: account3.clone(),
+			vault_balance_remained: 100,
+			account_balance_after: 100,
+			source_ref: None,
+		}.into());
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account3.clone().into(), account3.clone(), 50, None, Some(public.clone())));
+		assert_eq!(Assets::balance(token_id, &migration_vault_account), 500);
+
+		assert_noop!(
+			Migration::set_category(RuntimeOrigin::root(), team.clone(), 50, Some(vesting)),
+			Error::<TestSuite>::CategoryCapBelowMigrated
+		);
+		assert_eq!(Migration::category_totals(), vec![
+			CategoryTotals { name: b"public".to_vec(), vault: public_vault, cap: 300, migrated: 50, vault_balance: 250 },
+			CategoryTotals { name: b"team".to_vec(), vault: team_vault, cap: 200, migrated: 100, vault_balance: 100 },
+		]);
+
+		// Only the amount migrated from the team category vests.
+		assert_eq!(Migration::vesting_locked(&account3), 100);
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 60),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 40));
+
+		System::set_block_number(60);
+		assert_eq!(Migration::vesting_locked(&account3), 50);
+		System::set_block_number(110);
+		assert_eq!(Migration::vesting_locked(&account3), 0);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 110));
+	});
+}
+
+#[test]
+fn vesting_set_later_applies_to_amounts_already_migrated() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let team: CategoryName<TestSuite> = b"team".to_vec().try_into().unwrap();
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team.clone(), 200, None));
+		let team_vault = Migration::category_vault(&team);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(migration_vault_account.clone()), token_id.into(), team_vault.into(), 200));
+
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 100, None, Some(team.clone())));
+		assert_eq!(Migration::category_amount(&account3, &team), 100);
+		assert_eq!(Migration::vesting_locked(&account3), 0);
+
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team.clone(), 200, Some(CategoryVesting { start: 10, duration: 100 })));
+		assert_eq!(Migration::vesting_locked(&account3), 100);
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 10),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+
+		// Removing the vesting releases them again.
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team, 200, None));
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 10));
+	});
+}
+
+#[test]
+fn categories_can_be_dry_run_queued_and_attested() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+	let pairs = attester_pairs(&[1, 2]);
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let team: CategoryName<TestSuite> = b"team".to_vec().try_into().unwrap();
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team.clone(), 300, Some(CategoryVesting { start: 10, duration: 100 })));
+		let team_vault = Migration::category_vault(&team);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(migration_vault_account.clone()), token_id.into(), team_vault.clone().into(), 300));
+
+		let request = |category: Option<Vec<u8>>| MigrationRequest {
+			for_account: account2.clone().into(),
+			account_to_credit: account3.clone(),
+			amount: 50,
+			source_ref: None,
+			category,
+		};
+		assert_eq!(Migration::dry_run_migrate(signer.clone(), vec![
+			request(Some(b"team".to_vec())),
+			request(Some(b"ecosystem".to_vec())),
+			request(Some(vec![b'c'; 100])),
+		]), vec![
+			MigrationDryRunOutcome::Success { vault_balance_remained: 250, account_balance_after: 50 },
+			MigrationDryRunOutcome::Failed(Error::<TestSuite>::UnknownCategory.into()),
+			MigrationDryRunOutcome::Failed(Error::<TestSuite>::UnknownCategory.into()),
+		]);
+		assert_eq!(Migration::category_amount(&account3, &team), 0);
+
+		let queued = QueuedMigration {
+			for_account: account2.clone().into(),
+			account_to_credit: account3.clone(),
+			amount: 50,
+			source_ref: None,
+			category: Some(team.clone()),
+		};
+		assert_ok!(Migration::enqueue_migrations(RuntimeOrigin::signed(signer.clone()), vec![queued].try_into().unwrap()));
+		Migration::on_idle(1, Weight::MAX);
+		assert_eq!(Migration::category_amount(&account3, &team), 50);
+		assert_eq!(Assets::balance(token_id, &team_vault), 250);
+
+		// Attesters sign the category along with the other terms.
+		assert_ok!(Migration::set_attesters(RuntimeOrigin::root(), attesters(&pairs), 2));
+		let source_ref: SourceRef<TestSuite> = b"0xburn".to_vec().try_into().unwrap();
+		let for_account: SourceAccount = account2.clone().into();
+		let without_category = Migration::attestation_payload(&source_ref, &for_account, &account3, 100, None);
+		assert_noop!(
+			Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account3.clone(), 100, source_ref.clone(), Some(team.clone()), attest(&pairs, &[0, 1], &without_category)),
+			Error::<TestSuite>::InvalidAttestation
+		);
+		let payload = Migration::attestation_payload(&source_ref, &for_account, &account3, 100, Some(&team));
+		assert_ok!(Migration::migrate_attested(RuntimeOrigin::none(), for_account.clone(), account3.clone(), 100, source_ref, Some(team.clone()), attest(&pairs, &[0, 1], &payload)));
+		assert_eq!(Migration::category_amount(&account3, &team), 150);
+		assert_eq!(Migration::vesting_locked(&account3), 150);
+	});
+}
+
+#[test]
+fn migrations_only_credit_the_destination_bound_by_the_holder() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let holder = sr25519::Pair::from_seed(&[7; 32]);
+		let source = SourceAccount::Substrate(holder.public().0);
+		let bind = |destination: &AccountId| -> Signature { holder.sign(&Migration::destination_payload(&source, destination)).into() };
+
+		let other = sr25519::Pair::from_seed(&[8; 32]);
+		assert_noop!(
+			Migration::register_destination(RuntimeOrigin::signed(account2.clone()), source.clone(), account3.clone(), other.sign(&Migration::destination_payload(&source, &account3)).into()),
+			Error::<TestSuite>::InvalidSourceSignature
+		);
+		let signature = bind(&account3);
+		assert_ok!(Migration::register_destination(RuntimeOrigin::signed(account2.clone()), source.clone(), account3.clone(), signature.clone()));
+		System::assert_last_event(Event::DestinationRegistered { for_account: source.clone(), destination: account3.clone() }.into());
+		assert_eq!(Migration::bound_destination(&source), Some(account3.clone()));
+		assert_eq!(Migration::destination_binding_nonce(&source), 1);
+
+		// Other destinations are rejected in the pool and on dispatch.
+		let call = RuntimeCall::Migration(crate::Call::migrate {
+			for_account: source.clone(),
+			account_to_credit: account2.clone(),
+			amount: 50,
+			source_ref: None,
+			category: None,
+		});
+		assert_eq!(validate_with_extension(&signer, &call), Err(InvalidTransaction::Custom(DESTINATION_MISMATCH).into()));
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), source.clone(), account2.clone(), 50, None, None),
+			Error::<TestSuite>::DestinationMismatch
+		);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), source.clone(), account3.clone(), 50, None, None));
+		assert_eq!(Assets::balance(token_id, &account3), 50);
+
+		// The holder can bind another destination, but older signatures cannot be replayed.
+		assert_ok!(Migration::register_destination(RuntimeOrigin::signed(account2.clone()), source.clone(), account2.clone(), bind(&account2)));
+		assert_noop!(
+			Migration::register_destination(RuntimeOrigin::signed(account2.clone()), source.clone(), account3.clone(), signature),
+			Error::<TestSuite>::InvalidSourceSignature
+		);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), source.clone(), account2.clone(), 50, None, None));
+		assert_eq!(Assets::balance(token_id, &account2), 50);
+	});
+}
+
+#[test]
+fn ethereum_holders_bind_destinations_with_personal_sign() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let from_hex = |hex: &str| sp_core::bytes::from_hex(hex).unwrap();
+		let address = H160::from_slice(&from_hex("2c7536e3605d9c16a7a3d7b1898e529396a65c23"));
+
+		// Test vector of `personal_sign`.
+		let signature = ecdsa::Signature::from_raw(from_hex("b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c").try_into().unwrap());
+		assert_eq!(ethereum_signer(&signature, b"Some data"), Some(address));
+
+		let holder = ecdsa::Pair::from_seed_slice(&from_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")).unwrap();
+		let signature = holder.sign_prehashed(&personal_message_hash(&MigrationEth::destination_payload(&address, &account3)));
+		assert_noop!(
+			MigrationEth::register_destination(RuntimeOrigin::signed(account3.clone()), H160::repeat_byte(1), account3.clone(), signature.clone()),
+			Error::<TestSuite, Instance1>::InvalidSourceSignature
+		);
+		assert_ok!(MigrationEth::register_destination(RuntimeOrigin::signed(account3.clone()), address, account3.clone(), signature));
+
+		assert_noop!(
+			MigrationEth::migrate(RuntimeOrigin::signed(eth_migration_owner_account()), address, account2.clone(), 50, None, None),
+			Error::<TestSuite, Instance1>::DestinationMismatch
+		);
+		assert_ok!(MigrationEth::migrate(RuntimeOrigin::signed(eth_migration_owner_account()), address, account3.clone(), 50, None, None));
+		assert_eq!(Assets::balance(ETH_TOKEN_ID, &account3), 50);
+
+		// Bindings are kept per instance.
+		assert_eq!(Migration::bound_destination(SourceAccount::from(address)), None);
+	});
+}
+
+#[test]
+fn disputes_freeze_credits_until_they_are_redirected_or_released() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let holder_key = sr25519::Pair::from_seed(&[7; 32]);
+		let holder = SourceAccount::Substrate(holder_key.public().0);
+		let other_holder_key = sr25519::Pair::from_seed(&[8; 32]);
+		let other_holder = SourceAccount::Substrate(other_holder_key.public().0);
+		let evidence_hash = H256::repeat_byte(7);
+		let sign = |key: &sr25519::Pair, source: &SourceAccount| -> Signature { key.sign(&Migration::dispute_payload(source, &evidence_hash)).into() };
+
+		assert_noop!(
+			Migration::dispute(RuntimeOrigin::signed(account3.clone()), holder.clone(), evidence_hash, sign(&holder_key, &holder)),
+			Error::<TestSuite>::NothingCredited
+		);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), holder.clone(), account2.clone(), 50, None, None));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), holder.clone(), account1.clone(), 20, None, None));
+		assert_eq!(Migration::credited(&holder, &account2), 50);
+		// Part of the wrong credit is spent before the dispute.
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account2.clone()), token_id.into(), account1.clone().into(), 20));
+
+		// Only the holder of the source account can open a dispute.
+		assert_noop!(
+			Migration::dispute(RuntimeOrigin::signed(account3.clone()), holder.clone(), evidence_hash, sign(&other_holder_key, &holder)),
+			Error::<TestSuite>::InvalidSourceSignature
+		);
+
+		// Opening a dispute reserves the bond and freezes the credits.
+		let signature = sign(&holder_key, &holder);
+		assert_ok!(Migration::dispute(RuntimeOrigin::signed(account3.clone()), holder.clone(), evidence_hash, signature.clone()));
+		System::assert_last_event(Event::DisputeOpened { id: 0, for_account: holder.clone(), disputer: account3.clone(), evidence_hash }.into());
+		assert_eq!(Balances::reserved_balance(&account3), 100);
+		assert_eq!(Migration::open_dispute(&holder), Some(0));
+		assert_eq!(Migration::dispute_nonce(&holder), 1);
+		assert_eq!(Migration::disputed_balance(&account2), 50);
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account2.clone()), token_id.into(), account1.clone().into(), 10),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+		assert_noop!(
+			Migration::dispute(RuntimeOrigin::signed(account2.clone()), holder.clone(), evidence_hash, sign(&holder_key, &holder)),
+			Error::<TestSuite>::DisputeAlreadyOpen
+		);
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(signer.clone()), holder.clone(), account2.clone(), 10, None, None),
+			Error::<TestSuite>::SourceAccountDisputed
+		);
+
+		// Redirecting moves what is left of the credits and returns the bond.
+		assert_noop!(
+			Migration::resolve_dispute(RuntimeOrigin::signed(account3.clone()), 0, Some(account3.clone())),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(Migration::resolve_dispute(RuntimeOrigin::root(), 5, None), Error::<TestSuite>::UnknownDispute);
+		assert_ok!(Migration::resolve_dispute(RuntimeOrigin::root(), 0, Some(account3.clone())));
+		System::assert_last_event(Event::DisputeRedirected { id: 0, to: account3.clone(), amount: 50, shortfall: 20 }.into());
+		assert_eq!(Assets::balance(token_id, &account3), 50);
+		assert_eq!(Assets::balance(token_id, &account2), 0);
+		// The part that was spent stays credited to the wrong account.
+		assert_eq!(Migration::credited(&holder, &account2), 20);
+		assert_eq!(Migration::credited(&holder, &account1), 0);
+		assert_eq!(Migration::credited(&holder, &account3), 50);
+		assert_eq!(Migration::disputed_balance(&account2), 0);
+		assert_eq!(Balances::reserved_balance(&account3), 0);
+		assert_eq!(Migration::open_dispute(&holder), None);
+		assert_eq!(
+			Migration::dispute_info(0).unwrap().status,
+			DisputeStatus::Redirected { at: 1, to: account3.clone(), amount: 50, shortfall: 20 }
+		);
+		assert_noop!(Migration::resolve_dispute(RuntimeOrigin::root(), 0, None), Error::<TestSuite>::DisputeNotOpen);
+
+		// The signature of a resolved dispute cannot be replayed.
+		assert_noop!(
+			Migration::dispute(RuntimeOrigin::signed(account3.clone()), holder.clone(), evidence_hash, signature),
+			Error::<TestSuite>::InvalidSourceSignature
+		);
+
+		// Releasing unfreezes the credits and slashes the bond.
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), other_holder.clone(), account1.clone(), 30, None, None));
+		assert_ok!(Migration::dispute(RuntimeOrigin::signed(account2.clone()), other_holder.clone(), evidence_hash, sign(&other_holder_key, &other_holder)));
+		assert_eq!(Migration::disputed_balance(&account1), 30);
+		let free_balance = Balances::free_balance(&account2);
+		System::set_block_number(2);
+		assert_ok!(Migration::resolve_dispute(RuntimeOrigin::root(), 1, None));
+		System::assert_last_event(Event::DisputeReleased { id: 1 }.into());
+		assert_eq!(Balances::reserved_balance(&account2), 0);
+		assert_eq!(Balances::free_balance(&account2), free_balance);
+		assert_eq!(Migration::dispute_info(1).unwrap().status, DisputeStatus::Released { at: 2 });
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account1.clone()), token_id.into(), account2.clone().into(), 30));
+	});
+}
+
+#[test]
+fn redirected_disputes_move_category_vesting_along() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account.clone(), account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let team: CategoryName<TestSuite> = b"team".to_vec().try_into().unwrap();
+		assert_ok!(Migration::set_category(RuntimeOrigin::root(), team.clone(), 200, Some(CategoryVesting { start: 10, duration: 100 })));
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(migration_vault_account.clone()), token_id.into(), Migration::category_vault(&team).into(), 200));
+
+		let holder_key = sr25519::Pair::from_seed(&[7; 32]);
+		let holder = SourceAccount::Substrate(holder_key.public().0);
+		let evidence_hash = H256::repeat_byte(7);
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), holder.clone(), account2.clone(), 100, None, Some(team.clone())));
+		assert_eq!(Migration::vesting_locked(&account2), 100);
+
+		let signature: Signature = holder_key.sign(&Migration::dispute_payload(&holder, &evidence_hash)).into();
+		assert_ok!(Migration::dispute(RuntimeOrigin::signed(account3.clone()), holder.clone(), evidence_hash, signature));
+		assert_ok!(Migration::resolve_dispute(RuntimeOrigin::root(), 0, Some(account3.clone())));
+		System::assert_last_event(Event::DisputeRedirected { id: 0, to: account3.clone(), amount: 100, shortfall: 0 }.into());
+
+		// The vesting tokens moved, and so did their vesting.
+		assert_eq!(Assets::balance(token_id, &account3), 100);
+		assert_eq!(Migration::category_amount(&account2, &team), 0);
+		assert_eq!(Migration::category_amount(&account3, &team), 100);
+		assert_eq!(Migration::vesting_locked(&account2), 0);
+		assert_eq!(Migration::vesting_locked(&account3), 100);
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 10),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn every_instance_freezes_its_own_asset() {
+	let users = get_users();
+	let account1 = users[0].clone();
+	let account2 = users[1].clone();
+	let account3 = users[2].clone();
+	let signer = account1.clone();
+	let migration_vault_account = get_account_id_from_seed::<sr25519::Public>("MigrationVault");
+
+	new_test_ext(users, signer.clone(), 1000, migration_vault_account, account1.clone()).execute_with(|| {
+		System::set_block_number(1);
+		let token_id = Migration::get_token_id().unwrap();
+		let from_hex = |hex: &str| sp_core::bytes::from_hex(hex).unwrap();
+		let holder = ecdsa::Pair::from_seed_slice(&from_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")).unwrap();
+		let address = H160::from_slice(&from_hex("2c7536e3605d9c16a7a3d7b1898e529396a65c23"));
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(signer.clone()), account2.clone().into(), account3.clone(), 100, None, None));
+		assert_ok!(MigrationEth::migrate(RuntimeOrigin::signed(eth_migration_owner_account()), address, account3.clone(), 100, None, None));
+
+		// The lock of `Instance1` freezes its asset only.
+		assert_ok!(MigrationEth::set_transfer_lock(RuntimeOrigin::root(), Some(10)));
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account3.clone()), ETH_TOKEN_ID.into(), account2.clone().into(), 10),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), token_id.into(), account2.clone().into(), 10));
+		assert_ok!(MigrationEth::set_transfer_lock(RuntimeOrigin::root(), None));
+
+		// So do its disputes.
+		let evidence_hash = H256::repeat_byte(7);
+		let signature = holder.sign_prehashed(&personal_message_hash(&MigrationEth::dispute_payload(&address, &evidence_hash)));
+		assert_ok!(MigrationEth::dispute(RuntimeOrigin::signed(account1.clone()), address, evidence_hash, signature));
+		assert_eq!(MigrationEth::disputed_balance(&account3), 100);
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(account3.clone()), ETH_TOKEN_ID.into(), account2.clone().into(), 10),
+			pallet_assets::Error::<TestSuite>::BalanceLow
+		);
+		assert_ok!(MigrationEth::resolve_dispute(RuntimeOrigin::root(), 0, None));
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(account3.clone()), ETH_TOKEN_ID.into(), account2.clone().into(), 10));
 	});
 }
@@ -1,12 +1,9 @@
-
-//! Autogenerated weights for pallet_migration
+//! Weights for pallet_migration
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-01-19, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! HOSTNAME: `ng95c4v0gg`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: None, DB CACHE: 1024
+//! NOT FINAL. These are not benchmark results: the base weights are rough hand estimates and
+//! only the storage accesses listed above each function were counted. They must be replaced by
+//! the output of the benchmarks in `benchmarking.rs` before a production release:
 
-// Executed Command:
 // ./target/release/node-template
 // benchmark
 // pallet
@@ -14,7 +11,7 @@
 // --steps=50
 // --repeat=20
 // --pallet=pallet_migration
-// --extrinsic=migrate
+// --extrinsic=*
 // --execution=wasm
 // --wasm-execution=compiled
 // --heap-pages=4096
@@ -31,6 +28,37 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_migration.
 pub trait WeightInfo {
 	fn migrate() -> Weight;
+	fn commit_allocations() -> Weight;
+	fn upload_allocations(n: u32, ) -> Weight;
+	fn seal_allocations() -> Weight;
+	fn set_transfer_lock() -> Weight;
+	fn lift_transfer_lock() -> Weight;
+	fn set_fee_rate() -> Weight;
+	fn add_sponsor() -> Weight;
+	fn remove_sponsor() -> Weight;
+	fn sponsor() -> Weight;
+	fn unsponsor() -> Weight;
+	fn set_source_state_root() -> Weight;
+	fn claim_with_storage_proof(n: u32, ) -> Weight;
+	fn initialize_light_client() -> Weight;
+	fn submit_finality_proof(p: u32, ) -> Weight;
+	fn claim_burn(n: u32, ) -> Weight;
+	fn add_relayer() -> Weight;
+	fn remove_relayer() -> Weight;
+	fn set_trusted_ethereum_block() -> Weight;
+	fn claim_erc20_burn(n: u32, r: u32, ) -> Weight;
+	fn set_attesters(a: u32, ) -> Weight;
+	fn migrate_attested(s: u32, ) -> Weight;
+	fn set_outbound_cap() -> Weight;
+	fn migrate_back() -> Weight;
+	fn confirm_release() -> Weight;
+	fn enqueue_migrations(n: u32, ) -> Weight;
+	fn process_queued_migration() -> Weight;
+	fn set_category() -> Weight;
+	fn register_destination() -> Weight;
+	fn dispute(c: u32, ) -> Weight;
+	fn resolve_dispute(c: u32, ) -> Weight;
+	fn recommit_allocations(n: u32, ) -> Weight;
 }
 
 /// Weights for pallet_migration using the Substrate node and recommended hardware.
@@ -39,15 +67,380 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: Migration MigrationVaultAccount (r:1 w:0)
 	// Storage: Migration MigrationOwner (r:1 w:0)
 	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration ProcessedSourceRefs (r:1 w:1)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CategoryAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
 	fn migrate() -> Weight {
-		// Minimum execution time: 64_000 nanoseconds.
-		Weight::from_ref_time(66_000_000)
-			.saturating_add(T::DbWeight::get().reads(7))
+		Weight::from_ref_time(93_000_000)
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:1)
+	// Storage: Migration AllocationUpload (r:0 w:1)
+	fn commit_allocations() -> Weight {
+		Weight::from_ref_time(22_000_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration AllocationUpload (r:1 w:1)
+	// Storage: Migration Allocations (r:1 w:1)
+	/// The range of component `n` is `[1, 500]`.
+	fn upload_allocations(n: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000)
+			.saturating_add(Weight::from_ref_time(7_900_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:1)
+	// Storage: Migration AllocationUpload (r:1 w:0)
+	fn seal_allocations() -> Weight {
+		Weight::from_ref_time(24_000_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration TransferLockUntil (r:0 w:1)
+	fn set_transfer_lock() -> Weight {
+		Weight::from_ref_time(13_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration TransferLockUntil (r:1 w:1)
+	fn lift_transfer_lock() -> Weight {
+		Weight::from_ref_time(10_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration FeeRate (r:0 w:1)
+	fn set_fee_rate() -> Weight {
+		Weight::from_ref_time(12_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsors (r:0 w:1)
+	fn add_sponsor() -> Weight {
+		Weight::from_ref_time(13_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsors (r:1 w:1)
+	fn remove_sponsor() -> Weight {
+		Weight::from_ref_time(16_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsors (r:1 w:0)
+	// Storage: Migration MigrationRecipients (r:1 w:0)
+	// Storage: Migration Sponsorships (r:1 w:1)
+	fn sponsor() -> Weight {
+		Weight::from_ref_time(24_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsorships (r:1 w:1)
+	fn unsponsor() -> Weight {
+		Weight::from_ref_time(17_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration SourceStateRoots (r:0 w:1)
+	fn set_source_state_root() -> Weight {
+		Weight::from_ref_time(13_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration SourceStateRoots (r:1 w:0)
+	// Storage: Migration ProvenClaims (r:1 w:1)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn claim_with_storage_proof(n: u32, ) -> Weight {
+		Weight::from_ref_time(109_000_000)
+			.saturating_add(Weight::from_ref_time(3_100_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	// Storage: Migration BestFinalizedSourceHeader (r:0 w:1)
+	// Storage: Migration FinalizedSourceRoots (r:0 w:1)
+	// Storage: Migration SourceAuthoritySetId (r:0 w:1)
+	// Storage: Migration SourceAuthorities (r:0 w:1)
+	fn initialize_light_client() -> Weight {
+		Weight::from_ref_time(26_000_000)
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	// Storage: Migration BestFinalizedSourceHeader (r:1 w:1)
+	// Storage: Migration SourceAuthoritySetId (r:1 w:0)
+	// Storage: Migration SourceAuthorities (r:1 w:0)
+	// Storage: Migration FinalizedSourceRoots (r:0 w:1)
+	/// The range of component `p` is `[1, 100]`.
+	fn submit_finality_proof(p: u32, ) -> Weight {
+		Weight::from_ref_time(88_000_000)
+			.saturating_add(Weight::from_ref_time(52_400_000).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Migration FinalizedSourceRoots (r:1 w:0)
+	// Storage: Migration ClaimedBurns (r:1 w:1)
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn claim_burn(n: u32, ) -> Weight {
+		Weight::from_ref_time(111_000_000)
+			.saturating_add(Weight::from_ref_time(3_300_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	// Storage: Migration EthereumRelayers (r:0 w:1)
+	fn add_relayer() -> Weight {
+		Weight::from_ref_time(15_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration EthereumRelayers (r:1 w:1)
+	fn remove_relayer() -> Weight {
+		Weight::from_ref_time(19_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration EthereumRelayers (r:1 w:0)
+	// Storage: Migration TrustedEthereumBlocks (r:0 w:1)
+	fn set_trusted_ethereum_block() -> Weight {
+		Weight::from_ref_time(18_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration TrustedEthereumBlocks (r:1 w:0)
+	// Storage: Migration ClaimedReceipts (r:1 w:1)
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	/// The range of component `r` is `[1, 16384]`.
+	fn claim_erc20_burn(n: u32, r: u32, ) -> Weight {
+		Weight::from_ref_time(101_000_000)
+			.saturating_add(Weight::from_ref_time(2_600_000).saturating_mul(n.into()))
+			.saturating_add(Weight::from_ref_time(4_000).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	// Storage: Migration AttesterSetId (r:1 w:1)
+	// Storage: Migration Attesters (r:0 w:1)
+	// Storage: Migration AttesterThreshold (r:0 w:1)
+	/// The range of component `a` is `[1, 16]`.
+	fn set_attesters(a: u32, ) -> Weight {
+		Weight::from_ref_time(21_000_000)
+			.saturating_add(Weight::from_ref_time(310_000).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Migration AttesterThreshold (r:1 w:0)
+	// Storage: Migration Attesters (r:1 w:0)
+	// Storage: Migration AttesterSetId (r:2 w:0)
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration ProcessedSourceRefs (r:1 w:1)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CategoryAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `s` is `[1, 16]`.
+	fn migrate_attested(s: u32, ) -> Weight {
+		Weight::from_ref_time(92_000_000)
+			.saturating_add(Weight::from_ref_time(47_800_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(23))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	// Storage: Migration OutboundCap (r:0 w:1)
+	fn set_outbound_cap() -> Weight {
+		Weight::from_ref_time(14_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Migration OutboundCap (r:1 w:0)
+	// Storage: Migration PendingOutbound (r:1 w:1)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration OutboundNonce (r:1 w:1)
+	// Storage: Migration PendingReleases (r:0 w:1)
+	fn migrate_back() -> Weight {
+		Weight::from_ref_time(74_000_000)
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration PendingReleases (r:1 w:1)
+	// Storage: Migration PendingOutbound (r:1 w:1)
+	fn confirm_release() -> Weight {
+		Weight::from_ref_time(23_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration MigrationQueueHead (r:1 w:0)
+	// Storage: Migration MigrationQueueTail (r:1 w:1)
+	// Storage: Migration MigrationQueue (r:0 w:100)
+	/// The range of component `n` is `[1, 100]`.
+	fn enqueue_migrations(n: u32, ) -> Weight {
+		Weight::from_ref_time(21_000_000)
+			.saturating_add(Weight::from_ref_time(3_100_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	// Storage: Migration MigrationQueue (r:1 w:1)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration ProcessedSourceRefs (r:1 w:1)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CategoryAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	// Storage: Migration MigrationQueueHead (r:0 w:1)
+	fn process_queued_migration() -> Weight {
+		Weight::from_ref_time(93_000_000)
+			.saturating_add(T::DbWeight::get().reads(19))
+			.saturating_add(T::DbWeight::get().writes(12))
+	}
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CounterForCategories (r:1 w:1)
+	// Storage: Migration CategoryVaults (r:0 w:1)
+	fn set_category() -> Weight {
+		Weight::from_ref_time(20_000_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Migration DestinationBindingNonces (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:0 w:1)
+	fn register_destination() -> Weight {
+		Weight::from_ref_time(63_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Migration OpenDisputes (r:1 w:1)
+	// Storage: Migration DisputeNonces (r:1 w:1)
+	// Storage: Migration Credits (r:17 w:0)
+	// Storage: Migration NextDisputeId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration DisputedBalances (r:16 w:16)
+	// Storage: Migration Disputes (r:0 w:1)
+	/// The range of component `c` is `[1, 16]`.
+	fn dispute(c: u32, ) -> Weight {
+		Weight::from_ref_time(107_000_000)
+			.saturating_add(Weight::from_ref_time(7_400_000).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+	}
+	// Storage: Migration Disputes (r:1 w:1)
+	// Storage: Migration DisputedBalances (r:16 w:16)
+	// Storage: Migration CategoryAmounts (r:16 w:32)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:17 w:17)
+	// Storage: Migration Credits (r:1 w:17)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration OpenDisputes (r:0 w:1)
+	/// The range of component `c` is `[1, 16]`.
+	fn resolve_dispute(c: u32, ) -> Weight {
+		Weight::from_ref_time(62_000_000)
+			.saturating_add(Weight::from_ref_time(38_500_000).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(7))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(c.into())))
+	}
+	// Storage: Migration AllocationCommitment (r:1 w:1)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration AllocationUpload (r:1 w:1)
+	// Storage: Migration Allocations (r:0 w:500)
+	/// The range of component `n` is `[0, 500]`.
+	fn recommit_allocations(n: u32, ) -> Weight {
+		Weight::from_ref_time(28_000_000)
+			.saturating_add(Weight::from_ref_time(1_300_000).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 }
 
 // For backwards compatibility and tests
@@ -55,13 +448,378 @@ impl WeightInfo for () {
 	// Storage: Migration MigrationVaultAccount (r:1 w:0)
 	// Storage: Migration MigrationOwner (r:1 w:0)
 	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration ProcessedSourceRefs (r:1 w:1)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CategoryAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
 	fn migrate() -> Weight {
-		// Minimum execution time: 64_000 nanoseconds.
-		Weight::from_ref_time(66_000_000)
-			.saturating_add(RocksDbWeight::get().reads(7))
+		Weight::from_ref_time(93_000_000)
+			.saturating_add(RocksDbWeight::get().reads(18))
+			.saturating_add(RocksDbWeight::get().writes(10))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:1)
+	// Storage: Migration AllocationUpload (r:0 w:1)
+	fn commit_allocations() -> Weight {
+		Weight::from_ref_time(22_000_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration AllocationUpload (r:1 w:1)
+	// Storage: Migration Allocations (r:1 w:1)
+	/// The range of component `n` is `[1, 500]`.
+	fn upload_allocations(n: u32, ) -> Weight {
+		Weight::from_ref_time(25_000_000)
+			.saturating_add(Weight::from_ref_time(7_900_000).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:1)
+	// Storage: Migration AllocationUpload (r:1 w:0)
+	fn seal_allocations() -> Weight {
+		Weight::from_ref_time(24_000_000)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration TransferLockUntil (r:0 w:1)
+	fn set_transfer_lock() -> Weight {
+		Weight::from_ref_time(13_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration TransferLockUntil (r:1 w:1)
+	fn lift_transfer_lock() -> Weight {
+		Weight::from_ref_time(10_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration FeeRate (r:0 w:1)
+	fn set_fee_rate() -> Weight {
+		Weight::from_ref_time(12_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsors (r:0 w:1)
+	fn add_sponsor() -> Weight {
+		Weight::from_ref_time(13_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsors (r:1 w:1)
+	fn remove_sponsor() -> Weight {
+		Weight::from_ref_time(16_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsors (r:1 w:0)
+	// Storage: Migration MigrationRecipients (r:1 w:0)
+	// Storage: Migration Sponsorships (r:1 w:1)
+	fn sponsor() -> Weight {
+		Weight::from_ref_time(24_000_000)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration Sponsorships (r:1 w:1)
+	fn unsponsor() -> Weight {
+		Weight::from_ref_time(17_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration SourceStateRoots (r:0 w:1)
+	fn set_source_state_root() -> Weight {
+		Weight::from_ref_time(13_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration SourceStateRoots (r:1 w:0)
+	// Storage: Migration ProvenClaims (r:1 w:1)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn claim_with_storage_proof(n: u32, ) -> Weight {
+		Weight::from_ref_time(109_000_000)
+			.saturating_add(Weight::from_ref_time(3_100_000).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(18))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+	// Storage: Migration BestFinalizedSourceHeader (r:0 w:1)
+	// Storage: Migration FinalizedSourceRoots (r:0 w:1)
+	// Storage: Migration SourceAuthoritySetId (r:0 w:1)
+	// Storage: Migration SourceAuthorities (r:0 w:1)
+	fn initialize_light_client() -> Weight {
+		Weight::from_ref_time(26_000_000)
 			.saturating_add(RocksDbWeight::get().writes(4))
 	}
+	// Storage: Migration BestFinalizedSourceHeader (r:1 w:1)
+	// Storage: Migration SourceAuthoritySetId (r:1 w:0)
+	// Storage: Migration SourceAuthorities (r:1 w:0)
+	// Storage: Migration FinalizedSourceRoots (r:0 w:1)
+	/// The range of component `p` is `[1, 100]`.
+	fn submit_finality_proof(p: u32, ) -> Weight {
+		Weight::from_ref_time(88_000_000)
+			.saturating_add(Weight::from_ref_time(52_400_000).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: Migration FinalizedSourceRoots (r:1 w:0)
+	// Storage: Migration ClaimedBurns (r:1 w:1)
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	fn claim_burn(n: u32, ) -> Weight {
+		Weight::from_ref_time(111_000_000)
+			.saturating_add(Weight::from_ref_time(3_300_000).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(18))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+	// Storage: Migration EthereumRelayers (r:0 w:1)
+	fn add_relayer() -> Weight {
+		Weight::from_ref_time(15_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration EthereumRelayers (r:1 w:1)
+	fn remove_relayer() -> Weight {
+		Weight::from_ref_time(19_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration EthereumRelayers (r:1 w:0)
+	// Storage: Migration TrustedEthereumBlocks (r:0 w:1)
+	fn set_trusted_ethereum_block() -> Weight {
+		Weight::from_ref_time(18_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration TrustedEthereumBlocks (r:1 w:0)
+	// Storage: Migration ClaimedReceipts (r:1 w:1)
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `n` is `[1, 16]`.
+	/// The range of component `r` is `[1, 16384]`.
+	fn claim_erc20_burn(n: u32, r: u32, ) -> Weight {
+		Weight::from_ref_time(101_000_000)
+			.saturating_add(Weight::from_ref_time(2_600_000).saturating_mul(n.into()))
+			.saturating_add(Weight::from_ref_time(4_000).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(18))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+	// Storage: Migration AttesterSetId (r:1 w:1)
+	// Storage: Migration Attesters (r:0 w:1)
+	// Storage: Migration AttesterThreshold (r:0 w:1)
+	/// The range of component `a` is `[1, 16]`.
+	fn set_attesters(a: u32, ) -> Weight {
+		Weight::from_ref_time(21_000_000)
+			.saturating_add(Weight::from_ref_time(310_000).saturating_mul(a.into()))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: Migration AttesterThreshold (r:1 w:0)
+	// Storage: Migration Attesters (r:1 w:0)
+	// Storage: Migration AttesterSetId (r:2 w:0)
+	// Storage: Migration MigrationOwner (r:2 w:0)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration ProcessedSourceRefs (r:1 w:1)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CategoryAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	/// The range of component `s` is `[1, 16]`.
+	fn migrate_attested(s: u32, ) -> Weight {
+		Weight::from_ref_time(92_000_000)
+			.saturating_add(Weight::from_ref_time(47_800_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(23))
+			.saturating_add(RocksDbWeight::get().writes(10))
+	}
+	// Storage: Migration OutboundCap (r:0 w:1)
+	fn set_outbound_cap() -> Weight {
+		Weight::from_ref_time(14_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: Migration OutboundCap (r:1 w:0)
+	// Storage: Migration PendingOutbound (r:1 w:1)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration OutboundNonce (r:1 w:1)
+	// Storage: Migration PendingReleases (r:0 w:1)
+	fn migrate_back() -> Weight {
+		Weight::from_ref_time(74_000_000)
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration PendingReleases (r:1 w:1)
+	// Storage: Migration PendingOutbound (r:1 w:1)
+	fn confirm_release() -> Weight {
+		Weight::from_ref_time(23_000_000)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration MigrationQueueHead (r:1 w:0)
+	// Storage: Migration MigrationQueueTail (r:1 w:1)
+	// Storage: Migration MigrationQueue (r:0 w:100)
+	/// The range of component `n` is `[1, 100]`.
+	fn enqueue_migrations(n: u32, ) -> Weight {
+		Weight::from_ref_time(21_000_000)
+			.saturating_add(Weight::from_ref_time(3_100_000).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
+	// Storage: Migration MigrationQueue (r:1 w:1)
+	// Storage: Migration MigrationVaultAccount (r:1 w:0)
+	// Storage: Migration MigrationOwner (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Migration ProcessedSourceRefs (r:1 w:1)
+	// Storage: Migration AllocationCommitment (r:1 w:0)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration Allocations (r:1 w:0)
+	// Storage: Migration MigratedAmounts (r:1 w:1)
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CategoryAmounts (r:1 w:1)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:1 w:0)
+	// Storage: Migration OpenDisputes (r:1 w:0)
+	// Storage: Migration Credits (r:1 w:1)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	// Storage: Migration MigrationQueueHead (r:0 w:1)
+	fn process_queued_migration() -> Weight {
+		Weight::from_ref_time(93_000_000)
+			.saturating_add(RocksDbWeight::get().reads(19))
+			.saturating_add(RocksDbWeight::get().writes(12))
+	}
+	// Storage: Migration Categories (r:1 w:1)
+	// Storage: Migration CounterForCategories (r:1 w:1)
+	// Storage: Migration CategoryVaults (r:0 w:1)
+	fn set_category() -> Weight {
+		Weight::from_ref_time(20_000_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: Migration DestinationBindingNonces (r:1 w:1)
+	// Storage: Migration BoundDestinations (r:0 w:1)
+	fn register_destination() -> Weight {
+		Weight::from_ref_time(63_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: Migration OpenDisputes (r:1 w:1)
+	// Storage: Migration DisputeNonces (r:1 w:1)
+	// Storage: Migration Credits (r:17 w:0)
+	// Storage: Migration NextDisputeId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration DisputedBalances (r:16 w:16)
+	// Storage: Migration Disputes (r:0 w:1)
+	/// The range of component `c` is `[1, 16]`.
+	fn dispute(c: u32, ) -> Weight {
+		Weight::from_ref_time(107_000_000)
+			.saturating_add(Weight::from_ref_time(7_400_000).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(5))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(c.into())))
+	}
+	// Storage: Migration Disputes (r:1 w:1)
+	// Storage: Migration DisputedBalances (r:16 w:16)
+	// Storage: Migration CategoryAmounts (r:16 w:32)
+	// Storage: Migration TransferLockUntil (r:1 w:0)
+	// Storage: Migration TokenId (r:1 w:0)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:17 w:17)
+	// Storage: Migration Credits (r:1 w:17)
+	// Storage: Migration MigrationRecipients (r:0 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Migration OpenDisputes (r:0 w:1)
+	/// The range of component `c` is `[1, 16]`.
+	fn resolve_dispute(c: u32, ) -> Weight {
+		Weight::from_ref_time(62_000_000)
+			.saturating_add(Weight::from_ref_time(38_500_000).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes(7))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(c.into())))
+	}
+	// Storage: Migration AllocationCommitment (r:1 w:1)
+	// Storage: Migration AllocationsSealed (r:1 w:0)
+	// Storage: Migration AllocationUpload (r:1 w:1)
+	// Storage: Migration Allocations (r:0 w:500)
+	/// The range of component `n` is `[0, 500]`.
+	fn recommit_allocations(n: u32, ) -> Weight {
+		Weight::from_ref_time(28_000_000)
+			.saturating_add(Weight::from_ref_time(1_300_000).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 }
\ No newline at end of file
@@ -9,16 +9,21 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
+use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
-use frame_support::traits::Currency;
+use frame_support::traits::{
+	fungibles::{Balanced, CreditOf},
+	Currency, FindAuthor, Imbalance, OnUnbalanced, PalletInfoAccess, QueryPreimage,
+};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify, Identity
+		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify, Identity,
+		StaticLookup, SaturatedConversion,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
 use sp_std::prelude::*;
@@ -31,7 +36,7 @@ use sp_core::sr25519::Public;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo, AsEnsureOriginWithArg,
+		ConstU128, ConstU32, ConstU64, ConstU8, Contains, EitherOfDiverse, InstanceFilter, KeyOwnerProofSystem, Randomness, StorageInfo, AsEnsureOriginWithArg,
 	},
 	weights::{
 		constants::{
@@ -39,20 +44,24 @@ pub use frame_support::{
 		},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
-use frame_system::{EnsureSigned};
+use frame_support::dispatch::DispatchClass;
+use frame_system::{EnsureRoot, EnsureSigned, EnsureWithSuccess};
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{Perbill, Permill, Percent, RuntimeDebug};
 
 /// Import the template pallet.
 pub use pallet_migration;
 
+#[cfg(test)]
+mod tests;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -139,16 +148,28 @@ pub fn native_version() -> NativeVersion {
 }
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
+/// We allow for 2 seconds of compute with a 6 second average block time.
+const MAXIMUM_BLOCK_WEIGHT: Weight = Weight::from_parts(2u64 * WEIGHT_REF_TIME_PER_SECOND, u64::MAX);
 
 parameter_types! {
 	pub const BlockHashCount: BlockNumber = 2400;
 	pub const Version: RuntimeVersion = VERSION;
-	/// We allow for 2 seconds of compute with a 6 second average block time.
-	pub BlockWeights: frame_system::limits::BlockWeights =
-		frame_system::limits::BlockWeights::with_sensible_defaults(
-			Weight::from_parts(2u64 * WEIGHT_REF_TIME_PER_SECOND, u64::MAX),
-			NORMAL_DISPATCH_RATIO,
-		);
+	/// The sensible defaults, except that the base weight of an extrinsic includes a query of
+	/// the `Migration` freezer, which charging fees in the migrated asset makes.
+	pub BlockWeights: frame_system::limits::BlockWeights = frame_system::limits::BlockWeights::builder()
+		.for_class(DispatchClass::all(), |weights| {
+			weights.base_extrinsic = ExtrinsicBaseWeight::get()
+				.saturating_add(<Migration as pallet_migration::FreezerWeight>::frozen_balance());
+		})
+		.for_class(DispatchClass::Normal, |weights| {
+			weights.max_total = Some(NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT);
+		})
+		.for_class(DispatchClass::Operational, |weights| {
+			weights.max_total = Some(MAXIMUM_BLOCK_WEIGHT);
+			weights.reserved = Some(MAXIMUM_BLOCK_WEIGHT - NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT);
+		})
+		.avg_block_initialization(Perbill::from_percent(10))
+		.build_or_panic();
 	pub BlockLength: frame_system::limits::BlockLength = frame_system::limits::BlockLength
 		::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
 	pub const SS58Prefix: u8 = 42;
@@ -158,7 +179,7 @@ parameter_types! {
 
 impl frame_system::Config for Runtime {
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = MigratedAssetFilter;
 	/// Block & extrinsics weights: base values and limits.
 	type BlockWeights = BlockWeights;
 	/// The maximum length of a block (in bytes).
@@ -267,16 +288,17 @@ impl pallet_assets::Config for Runtime {
 	type AssetIdParameter = codec::Compact<u32>;
 	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
 	type Currency = Balances;
-	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureGovernance;
 	type AssetDeposit = ConstU128<1>;
 	type AssetAccountDeposit = ConstU128<1>;
 	type MetadataDepositBase = ConstU128<1>;
 	type MetadataDepositPerByte = ConstU128<1>;
 	type ApprovalDeposit = ConstU128<1>;
 	type StringLimit = ConstU32<50>;
-	type Freezer = ();
+	type Freezer = Migration;
 	type Extra = ();
-	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	// Transfers query the `Migration` freezer, which the assets weights don't include.
+	type WeightInfo = pallet_migration::FreezerWeightInfo<pallet_assets::weights::SubstrateWeight<Runtime>, Migration>;
 	type RemoveItemsLimit = ConstU32<1000>;
 	type CallbackHandle = ();
 	#[cfg(feature = "runtime-benchmarks")]
@@ -285,22 +307,278 @@ impl pallet_assets::Config for Runtime {
 
 parameter_types! {
 	pub FeeMultiplier: Multiplier = Multiplier::one();
+	/// Share of the transaction fees that goes to the treasury. The rest, and all tips, go to the
+	/// block author.
+	pub const TreasuryFeeShare: Percent = Percent::from_percent(80);
+}
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Finds the account of the block author from the Aura pre-runtime digest.
+pub struct AuraAccountAdapter;
+
+impl FindAuthor<AccountId> for AuraAccountAdapter {
+	fn find_author<'a, I>(digests: I) -> Option<AccountId>
+	where
+		I: 'a + IntoIterator<Item = (sp_runtime::ConsensusEngineId, &'a [u8])>,
+	{
+		pallet_aura::AuraAuthorId::<Runtime>::find_author(digests)
+			.and_then(|authority| AccountId::try_from(AsRef::<[u8]>::as_ref(&authority)).ok())
+	}
+}
+
+/// Account of the author of the current block.
+fn block_author() -> Option<AccountId> {
+	let digest = System::digest();
+	let pre_runtime_digests = digest.logs().iter().filter_map(|d| d.as_pre_runtime());
+	AuraAccountAdapter::find_author(pre_runtime_digests)
+}
+
+/// Pays the block author, or the treasury when the author is unknown.
+pub struct ToAuthor;
+
+impl OnUnbalanced<NegativeImbalance> for ToAuthor {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		match block_author() {
+			Some(author) => Balances::resolve_creating(&author, amount),
+			None => Treasury::on_unbalanced(amount),
+		}
+	}
+}
+
+/// Splits transaction fees between the treasury and the block author, instead of burning GAS.
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let treasury_share = TreasuryFeeShare::get().deconstruct() as u32;
+			let (to_treasury, mut to_author) = fees.ration(treasury_share, 100 - treasury_share);
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut to_author);
+			}
+			Treasury::on_unbalanced(to_treasury);
+			ToAuthor::on_unbalanced(to_author);
+		}
+	}
 }
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>; // charge from balances
+	// Charge from balances, of the sponsor for sponsored migration recipients.
+	type OnChargeTransaction = pallet_migration::SponsoredCurrencyAdapter<Runtime, (), CurrencyAdapter<Balances, DealWithFees>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightToFee = IdentityFee<Balance>;
 	type LengthToFee = IdentityFee<Balance>;
 	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
 }
 
+/// Pays fees charged in the migrated asset to the block author, so they are not burned.
+///
+/// When the author is unknown or can't receive them, e.g. because the fee is below the minimum
+/// balance of the asset, they go to the treasury account. If the treasury can't receive them
+/// either, they are burned; they never go to the migration vault, whose balance is reconciled
+/// against the migrated amounts.
+pub struct AssetFeesToAuthor;
+
+impl pallet_asset_tx_payment::HandleCredit<AccountId, Assets> for AssetFeesToAuthor {
+	fn handle_credit(mut credit: CreditOf<AccountId, Assets>) {
+		let recipients = block_author().into_iter().chain(Some(Treasury::account_id()));
+		for to in recipients {
+			match <Assets as Balanced<AccountId>>::resolve(&to, credit) {
+				Ok(()) => return,
+				Err(rest) => credit = rest,
+			}
+		}
+		// Dropping the credit burns it.
+		drop(credit);
+	}
+}
+
+impl pallet_asset_tx_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fungibles = Assets;
+	// Fees are converted at the rate set with `migration.setFeeRate`, and only the migrated
+	// asset can be used.
+	type OnChargeAssetTransaction = pallet_asset_tx_payment::FungiblesAdapter<Migration, AssetFeesToAuthor>;
+}
+
 impl pallet_sudo::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 }
 
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// Root, or more than half of the council. Administers the migration and the assets pallet.
+pub type EnsureGovernance = EitherOfDiverse<
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
+>;
+
+/// At least two thirds of the council. Whitelists calls and dispatches them as Root, which is how
+/// runtime upgrades are enacted.
+pub type EnsureCouncilSupermajority =
+	pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"jur/trsy");
+	pub const TreasuryProposalBond: Permill = Permill::from_percent(5);
+	pub const TreasuryProposalBondMinimum: Balance = 1_000_000_000_000;
+	pub const TreasurySpendPeriod: BlockNumber = DAYS;
+	pub const TreasuryBurn: Permill = Permill::zero();
+	pub const TreasuryMaxApprovals: u32 = 100;
+	/// Most GAS a single council motion can spend from the treasury.
+	pub const TreasuryMaxSpend: Balance = 1_000_000_000_000_000;
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	type ApproveOrigin = EnsureGovernance;
+	type RejectOrigin = EnsureGovernance;
+	type RuntimeEvent = RuntimeEvent;
+	type OnSlash = Treasury;
+	type ProposalBond = TreasuryProposalBond;
+	type ProposalBondMinimum = TreasuryProposalBondMinimum;
+	type ProposalBondMaximum = ();
+	type SpendPeriod = TreasurySpendPeriod;
+	type Burn = TreasuryBurn;
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = TreasuryMaxApprovals;
+	type SpendOrigin = EnsureWithSuccess<EnsureGovernance, AccountId, TreasuryMaxSpend>;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = 1_000_000_000_000;
+	pub const PreimageByteDeposit: Balance = 1_000_000;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureGovernance;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+impl pallet_whitelist::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WhitelistOrigin = EnsureCouncilSupermajority;
+	type DispatchWhitelistedOrigin = EnsureCouncilSupermajority;
+	type Preimages = Preimage;
+	type WeightInfo = pallet_whitelist::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MigrationTxPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	pub const MigrationPalletId: PalletId = PalletId(*b"jur/migr");
+	/// Balances are proven from `System::Account` of the source chain.
+	pub const MigrationSourceBalanceEntry: pallet_migration::SourceBalanceEntry = pallet_migration::SourceBalanceEntry::System;
+	pub const MigrationDisputeBond: Balance = 1_000_000_000_000;
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const MultisigDepositBase: Balance = 1_000_000_000_000 + 88 * 1_000_000;
+	// Additional storage item size of 32 bytes.
+	pub const MultisigDepositFactor: Balance = 32 * 1_000_000;
+	pub const MaxSignatories: u32 = 100;
+}
+
+impl pallet_multisig::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type DepositBase = MultisigDepositBase;
+	type DepositFactor = MultisigDepositFactor;
+	type MaxSignatories = MaxSignatories;
+	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = 1_000_000_000_000 + 40 * 1_000_000;
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = 33 * 1_000_000;
+	pub const AnnouncementDepositBase: Balance = 1_000_000_000_000 + 48 * 1_000_000;
+	pub const AnnouncementDepositFactor: Balance = 66 * 1_000_000;
+	pub const MaxProxies: u16 = 32;
+	pub const MaxPending: u16 = 32;
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// Any call.
+	Any,
+	/// Only calls of the migration pallet, for hot operators of the migration owner.
+	Migration,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, c: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::Migration => matches!(c, RuntimeCall::Migration(..)),
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			_ => false,
+		}
+	}
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = MaxPending;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+/// Account on the source chain tokens are migrated from.
+pub type SourceAccountId = [u8; 32];
+
 /// Configure the pallet-migration in pallets/migration.
 impl pallet_migration::Config for Runtime {
 	type TokenId = u32;
@@ -313,13 +591,150 @@ impl pallet_migration::Config for Runtime {
 	type MigrationVaultAccount = Public;
 	type MigrationOwner = Public;
 	type WeightInfo = pallet_migration::weights::SubstrateWeight<Runtime>;
-	
+	type SourceAccountId = SourceAccountId;
+	type MaxSourceRefLength = ConstU32<64>;
+	type AuthorityId = pallet_migration::crypto::MigrationAuthId;
+	type OffchainRetryDelay = ConstU32<10>;
+	type MaxOffchainRetries = ConstU32<5>;
+	type MaxOffchainSubmissions = ConstU32<16>;
+	type MaxAllocationChunk = ConstU32<500>;
+	type MigrationTxPriority = MigrationTxPriority;
+	type MigrationTxLongevity = ConstU64<64>;
+	type AdminOrigin = EnsureGovernance;
+	type PalletId = MigrationPalletId;
+	type MaxSponsoredPallets = ConstU32<8>;
+	type SourceBalanceEntry = MigrationSourceBalanceEntry;
+	type MaxProofNodes = ConstU32<16>;
+	type MaxSourceAuthorities = ConstU32<100>;
+	type SourceBurns = ();
+	type Erc20Source = ();
+	type MaxReceiptLength = ConstU32<16_384>;
+	type OnMigrated = ();
+	type AttesterId = pallet_migration::crypto::Public;
+	type MaxAttesters = ConstU32<16>;
+	type MaxQueuedMigrations = ConstU32<500>;
+	type MaxCategoryNameLength = ConstU32<32>;
+	type MaxCategories = ConstU32<16>;
+	type SourceSignature = Signature;
+	type DisputeBond = MigrationDisputeBond;
+	type MaxDisputedCredits = ConstU32<16>;
+	type DisputeSlash = Treasury;
+
 	#[cfg(feature = "runtime-benchmarks")]
 	type BenchmarkHelper = ();
 	#[cfg(feature = "runtime-benchmarks")]
 	type AssetIdParameter = codec::Compact<u32>;
 }
 
+/// Keeps the supply and the roles of the migrated asset fixed after genesis.
+///
+/// The asset is owned by the migration pallet account, which nobody can sign for. This filter
+/// additionally rejects every call that could mint, burn or reassign the roles of the migrated
+/// asset, including when wrapped in a sudo call or dispatched as the pallet account, as well as
+/// raw storage writes to the `Assets` and `Migration` pallets. Whitelisted calls are dispatched as
+/// root without the filter, so they are inspected here, and rejected if their preimage is unknown
+/// or undecodable.
+pub struct MigratedAssetFilter;
+
+impl Contains<RuntimeCall> for MigratedAssetFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		!changes_migrated_asset(call)
+	}
+}
+
+fn changes_migrated_asset(call: &RuntimeCall) -> bool {
+	let is_migrated = |id: &codec::Compact<u32>| Migration::get_token_id() == Some(id.0);
+	// Raw storage writes can't be narrowed down to the migrated asset, so any key in the storage
+	// of `Assets` or `Migration`, or any prefix covering it, is rejected.
+	let is_protected_key = |key: &[u8]| {
+		[<Assets as PalletInfoAccess>::name(), <Migration as PalletInfoAccess>::name()].iter().any(|name| {
+			let prefix = sp_io::hashing::twox_128(name.as_bytes());
+			key.starts_with(&prefix) || prefix.starts_with(key)
+		})
+	};
+
+	match call {
+		RuntimeCall::System(frame_system::Call::set_storage { items }) =>
+			items.iter().any(|(key, _)| is_protected_key(key)),
+		RuntimeCall::System(frame_system::Call::kill_storage { keys }) => keys.iter().any(|key| is_protected_key(key)),
+		RuntimeCall::System(frame_system::Call::kill_prefix { prefix, .. }) => is_protected_key(prefix),
+		RuntimeCall::Assets(
+			pallet_assets::Call::mint { id, .. } |
+			pallet_assets::Call::burn { id, .. } |
+			pallet_assets::Call::set_team { id, .. } |
+			pallet_assets::Call::transfer_ownership { id, .. } |
+			pallet_assets::Call::force_create { id, .. } |
+			pallet_assets::Call::force_asset_status { id, .. } |
+			pallet_assets::Call::start_destroy { id },
+		) => is_migrated(id),
+		RuntimeCall::Sudo(
+			pallet_sudo::Call::sudo { call } | pallet_sudo::Call::sudo_unchecked_weight { call, .. },
+		) => changes_migrated_asset(call),
+		RuntimeCall::Sudo(pallet_sudo::Call::sudo_as { who, call }) =>
+			<Runtime as frame_system::Config>::Lookup::lookup(who.clone()).ok() == Some(Migration::account_id()) ||
+				changes_migrated_asset(call),
+		RuntimeCall::Whitelist(pallet_whitelist::Call::dispatch_whitelisted_call_with_preimage { call }) =>
+			changes_migrated_asset(call),
+		RuntimeCall::Whitelist(pallet_whitelist::Call::dispatch_whitelisted_call { call_hash, call_encoded_len, .. }) =>
+			Preimage::fetch(call_hash, Some(*call_encoded_len))
+				.ok()
+				.and_then(|call| RuntimeCall::decode_all_with_depth_limit(sp_api::MAX_EXTRINSIC_DEPTH, &mut &call[..]).ok())
+				.map_or(true, |call| changes_migrated_asset(&call)),
+		_ => false,
+	}
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(RuntimeCall, <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+		let period = BlockHashCount::get()
+			.checked_next_power_of_two()
+			.map(|c| c / 2)
+			.unwrap_or(2) as u64;
+		let current_block = System::block_number()
+			.saturated_into::<u64>()
+			// The `System::block_number` is initialized with `n+1`,
+			// so the actual block number is `n`.
+			.saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::from(0, None),
+			pallet_migration::CheckMigration::<Runtime>::new(),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let address = <Runtime as frame_system::Config>::Lookup::unlookup(account);
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (address, signature, extra)))
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub struct Runtime
@@ -336,8 +751,15 @@ construct_runtime!(
 		Balances: pallet_balances,
 		Assets:  pallet_assets,
 		TransactionPayment: pallet_transaction_payment,
+		AssetTxPayment: pallet_asset_tx_payment,
 		Sudo: pallet_sudo,
 		Migration: pallet_migration,
+		Council: pallet_collective::<Instance1>,
+		Preimage: pallet_preimage,
+		Whitelist: pallet_whitelist,
+		Multisig: pallet_multisig,
+		Proxy: pallet_proxy,
+		Treasury: pallet_treasury,
 	}
 );
 
@@ -356,7 +778,8 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
+	pallet_migration::CheckMigration<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -386,6 +809,12 @@ mod benches {
 		[pallet_assets, Assets]
 		[pallet_timestamp, Timestamp]
 		[pallet_migration, Migration]
+		[pallet_collective, Council]
+		[pallet_preimage, Preimage]
+		[pallet_whitelist, Whitelist]
+		[pallet_multisig, Multisig]
+		[pallet_proxy, Proxy]
+		[pallet_treasury, Treasury]
 	);
 }
 
@@ -537,6 +966,31 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_migration_runtime_api::MigrationApi<Block, AccountId, SourceAccountId, Balance> for Runtime {
+		fn dry_run_migrate(
+			who: AccountId,
+			migrations: Vec<pallet_migration::MigrationRequest<SourceAccountId, AccountId, Balance>>,
+		) -> Vec<pallet_migration::MigrationDryRunOutcome<Balance>> {
+			Migration::dry_run_migrate(who, migrations)
+		}
+
+		fn pending_releases() -> Vec<(u64, pallet_migration::OutboundRelease<AccountId, SourceAccountId, Balance>)> {
+			Migration::pending_releases()
+		}
+
+		fn migration_queue_progress() -> pallet_migration::MigrationQueueProgress {
+			Migration::migration_queue_progress()
+		}
+
+		fn failed_migrations() -> Vec<(u32, pallet_migration::MigrationRequest<SourceAccountId, AccountId, Balance>)> {
+			Migration::failed_migrations()
+		}
+
+		fn category_totals() -> Vec<pallet_migration::CategoryTotals<AccountId, Balance>> {
+			Migration::category_totals()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
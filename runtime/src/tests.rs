@@ -0,0 +1,610 @@
+use crate::*;
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo, traits::{fungibles::Balanced, Currency, OnUnbalanced}};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::sr25519::Public;
+use frame_support::traits::tokens::BalanceConversion;
+use pallet_asset_tx_payment::{ChargeAssetTxPayment, HandleCredit};
+use sp_runtime::{traits::{BlakeTwo256, Dispatchable, Hash, SignedExtension}, AccountId32, DigestItem, DispatchError, FixedU128};
+
+const TOKEN_ID: u32 = 1;
+const OTHER_TOKEN_ID: u32 = 2;
+const VAULT_TOTAL: Balance = 1_000_000;
+
+fn alice() -> AccountId {
+	AccountId32::new([1; 32])
+}
+
+fn bob() -> AccountId {
+	AccountId32::new([2; 32])
+}
+
+fn charlie() -> AccountId {
+	AccountId32::new([4; 32])
+}
+
+fn vault() -> AccountId {
+	AccountId32::new([3; 32])
+}
+
+/// The account of the only Aura authority.
+fn author() -> AccountId {
+	AccountId32::new([5; 32])
+}
+
+/// The 2-of-3 multisig account of alice, bob and charlie.
+fn multisig() -> AccountId {
+	Multisig::multi_account_id(&[alice(), bob(), charlie()], 2)
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_owner(alice())
+}
+
+fn new_test_ext_with_owner(owner: AccountId) -> sp_io::TestExternalities {
+	let storage = GenesisConfig {
+		assets: AssetsConfig {
+			assets: vec![(TOKEN_ID, Migration::account_id(), true, 1)],
+			metadata: vec![],
+			accounts: vec![(TOKEN_ID, vault(), VAULT_TOTAL)],
+		},
+		balances: BalancesConfig { balances: vec![(alice(), 1 << 60), (bob(), 1 << 60), (multisig(), 1 << 60)] },
+		migration: MigrationConfig {
+			migration_vault_account: Some(vault()),
+			migration_owner: Some(owner),
+			asset_id: Some(TOKEN_ID),
+			transfer_lock_until: None,
+			source_state_roots: vec![],
+		},
+		aura: AuraConfig { authorities: vec![AuraId::from(Public::from_raw([5; 32]))] },
+		sudo: SudoConfig { key: Some(alice()) },
+		council: CouncilConfig { members: vec![alice(), bob(), charlie()], phantom: Default::default() },
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn mint(who: AccountId, amount: Balance) -> RuntimeCall {
+	RuntimeCall::Assets(pallet_assets::Call::mint { id: TOKEN_ID.into(), beneficiary: who.into(), amount })
+}
+
+#[test]
+fn migrated_asset_supply_is_fixed_after_genesis() {
+	new_test_ext().execute_with(|| {
+		let pallet_account = Migration::account_id();
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+
+		let attempts = vec![
+			// Neither the sudo key nor anyone else is the issuer.
+			(alice(), mint(alice(), 1)),
+			(alice(), RuntimeCall::Assets(pallet_assets::Call::set_team {
+				id: TOKEN_ID.into(),
+				issuer: alice().into(),
+				admin: alice().into(),
+				freezer: alice().into(),
+			})),
+			(alice(), RuntimeCall::Sudo(pallet_sudo::Call::sudo {
+				call: Box::new(RuntimeCall::Assets(pallet_assets::Call::force_asset_status {
+					id: TOKEN_ID.into(),
+					owner: alice().into(),
+					issuer: alice().into(),
+					admin: alice().into(),
+					freezer: alice().into(),
+					min_balance: 1,
+					is_sufficient: true,
+					is_frozen: false,
+				})),
+			})),
+			(alice(), RuntimeCall::Sudo(pallet_sudo::Call::sudo_as {
+				who: pallet_account.clone().into(),
+				call: Box::new(mint(alice(), 1)),
+			})),
+			(alice(), RuntimeCall::Sudo(pallet_sudo::Call::sudo {
+				call: Box::new(RuntimeCall::Sudo(pallet_sudo::Call::sudo_as {
+					who: bob().into(),
+					call: Box::new(RuntimeCall::Assets(pallet_assets::Call::burn {
+						id: TOKEN_ID.into(),
+						who: vault().into(),
+						amount: 1,
+					})),
+				})),
+			})),
+			(bob(), RuntimeCall::Assets(pallet_assets::Call::start_destroy { id: TOKEN_ID.into() })),
+		];
+		for (who, call) in attempts {
+			assert_eq!(
+				call.dispatch(RuntimeOrigin::signed(who)).map_err(|e| e.error),
+				Err(DispatchError::from(frame_system::Error::<Runtime>::CallFiltered))
+			);
+		}
+
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+		assert_eq!(Assets::balance(TOKEN_ID, vault()), VAULT_TOTAL);
+	});
+}
+
+#[test]
+fn whitelisted_calls_cannot_change_the_migrated_asset() {
+	new_test_ext().execute_with(|| {
+		let council = || RuntimeOrigin::from(pallet_collective::RawOrigin::<AccountId, pallet_collective::Instance1>::Members(3, 3));
+		let whitelisted = |call: RuntimeCall| {
+			let call_hash = BlakeTwo256::hash_of(&call);
+			assert_ok!(Whitelist::whitelist_call(council(), call_hash));
+			RuntimeCall::Whitelist(pallet_whitelist::Call::dispatch_whitelisted_call {
+				call_hash,
+				call_encoded_len: call.encode().len() as u32,
+				call_weight_witness: call.get_dispatch_info().weight,
+			})
+		};
+
+		let force_status = RuntimeCall::Assets(pallet_assets::Call::force_asset_status {
+			id: TOKEN_ID.into(),
+			owner: alice().into(),
+			issuer: alice().into(),
+			admin: alice().into(),
+			freezer: alice().into(),
+			min_balance: 1,
+			is_sufficient: true,
+			is_frozen: false,
+		});
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![1] });
+		let dispatch_force_status = whitelisted(force_status.clone());
+		let dispatch_remark = whitelisted(remark.clone());
+
+		// Without a preimage the call cannot be shown to be safe.
+		assert_eq!(
+			dispatch_force_status.clone().dispatch(council()).map_err(|e| e.error),
+			Err(DispatchError::from(frame_system::Error::<Runtime>::CallFiltered))
+		);
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(alice()), force_status.encode()));
+		assert_eq!(
+			dispatch_force_status.dispatch(council()).map_err(|e| e.error),
+			Err(DispatchError::from(frame_system::Error::<Runtime>::CallFiltered))
+		);
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(alice()), remark.encode()));
+		assert_ok!(dispatch_remark.dispatch(council()));
+	});
+}
+
+#[test]
+fn root_cannot_rewrite_migrated_asset_storage() {
+	new_test_ext().execute_with(|| {
+		let assets_prefix = sp_io::hashing::twox_128(b"Assets").to_vec();
+		let migration_prefix = sp_io::hashing::twox_128(b"Migration").to_vec();
+		let asset_key = [assets_prefix.clone(), sp_io::hashing::twox_128(b"Asset").to_vec()].concat();
+		let sudo = |call: frame_system::Call<Runtime>| {
+			RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(RuntimeCall::System(call)) })
+		};
+
+		let attempts = vec![
+			frame_system::Call::set_storage { items: vec![(asset_key.clone(), vec![])] },
+			frame_system::Call::kill_storage { keys: vec![b"other".to_vec(), asset_key.clone()] },
+			frame_system::Call::kill_prefix { prefix: assets_prefix, subkeys: 1 },
+			frame_system::Call::kill_prefix { prefix: migration_prefix, subkeys: 1 },
+			// Prefixes covering the pallet storage are rejected too.
+			frame_system::Call::kill_prefix { prefix: vec![], subkeys: 1 },
+		];
+		for call in attempts {
+			assert_eq!(
+				sudo(call).dispatch(RuntimeOrigin::signed(alice())).map_err(|e| e.error),
+				Err(DispatchError::from(frame_system::Error::<Runtime>::CallFiltered))
+			);
+		}
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+
+		// Other storage can still be written.
+		assert_ok!(sudo(frame_system::Call::set_storage { items: vec![(b"other".to_vec(), vec![1])] }).dispatch(RuntimeOrigin::signed(alice())));
+		assert_eq!(sp_io::storage::get(b"other").map(|value| value.to_vec()), Some(vec![1]));
+	});
+}
+
+#[test]
+fn migrated_asset_can_still_be_migrated_and_transferred() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(alice()), [2; 32], bob(), 100, None, None));
+		assert_ok!(RuntimeCall::Assets(pallet_assets::Call::transfer {
+			id: TOKEN_ID.into(),
+			target: alice().into(),
+			amount: 40,
+		})
+		.dispatch(RuntimeOrigin::signed(bob())));
+
+		assert_eq!(Assets::balance(TOKEN_ID, alice()), 40);
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+	});
+}
+
+#[test]
+fn other_assets_are_not_filtered() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create(RuntimeOrigin::signed(bob()), OTHER_TOKEN_ID.into(), bob().into(), 1));
+		assert_ok!(RuntimeCall::Assets(pallet_assets::Call::mint {
+			id: OTHER_TOKEN_ID.into(),
+			beneficiary: bob().into(),
+			amount: 10,
+		})
+		.dispatch(RuntimeOrigin::signed(bob())));
+
+		assert_eq!(Assets::total_supply(OTHER_TOKEN_ID), 10);
+	});
+}
+
+/// Propose `call` to the council as `proposer`, approve it with `ayes` and close the motion.
+fn pass_council_motion(proposer: AccountId, ayes: &[AccountId], call: RuntimeCall) {
+	let length = call.encoded_size() as u32;
+	let weight = call.get_dispatch_info().weight;
+	let hash = BlakeTwo256::hash_of(&call);
+	let index = Council::proposal_count();
+
+	let threshold = ayes.len() as u32 + 1;
+	assert_ok!(Council::propose(RuntimeOrigin::signed(proposer.clone()), threshold, Box::new(call), length));
+	for who in ayes {
+		assert_ok!(Council::vote(RuntimeOrigin::signed(who.clone()), hash, index, true));
+	}
+	assert_ok!(Council::close(RuntimeOrigin::signed(proposer), hash, index, weight, length));
+}
+
+#[test]
+fn migration_is_administered_by_council_majority() {
+	new_test_ext().execute_with(|| {
+		let call = RuntimeCall::Migration(pallet_migration::Call::set_transfer_lock { until: Some(100) });
+
+		// No single account, not even the sudo key, is an admin.
+		assert_noop!(Migration::set_transfer_lock(RuntimeOrigin::signed(alice()), Some(100)), DispatchError::BadOrigin);
+
+		// Two of three members are more than half of the council.
+		pass_council_motion(alice(), &[bob()], call);
+		assert_eq!(Migration::transfer_lock_until(), Some(100));
+	});
+}
+
+#[test]
+fn council_supermajority_dispatches_whitelisted_calls_as_root() {
+	new_test_ext().execute_with(|| {
+		// This is how runtime upgrades (`system.set_code`) are enacted once sudo is removed.
+		let call = RuntimeCall::System(frame_system::Call::set_heap_pages { pages: 42 });
+		let call_hash = BlakeTwo256::hash_of(&call);
+
+		pass_council_motion(alice(), &[bob()], RuntimeCall::Whitelist(pallet_whitelist::Call::whitelist_call { call_hash }));
+		pass_council_motion(
+			alice(),
+			&[bob()],
+			RuntimeCall::Whitelist(pallet_whitelist::Call::dispatch_whitelisted_call_with_preimage { call: Box::new(call) }),
+		);
+
+		assert_eq!(sp_io::storage::get(sp_core::storage::well_known_keys::HEAP_PAGES), Some(42u64.encode()));
+	});
+}
+
+/// Approve `call` as the multisig with alice and bob.
+fn dispatch_as_multisig(call: RuntimeCall) {
+	let max_weight = call.get_dispatch_info().weight;
+
+	assert_ok!(Multisig::as_multi(
+		RuntimeOrigin::signed(alice()),
+		2,
+		vec![bob(), charlie()],
+		None,
+		Box::new(call.clone()),
+		max_weight,
+	));
+	assert_ok!(Multisig::as_multi(
+		RuntimeOrigin::signed(bob()),
+		2,
+		vec![alice(), charlie()],
+		Some(Multisig::timepoint()),
+		Box::new(call),
+		max_weight,
+	));
+}
+
+#[test]
+fn multisig_account_can_own_migrations() {
+	new_test_ext_with_owner(multisig()).execute_with(|| {
+		assert_noop!(
+			Migration::migrate(RuntimeOrigin::signed(alice()), [2; 32], bob(), 100, None, None),
+			pallet_migration::Error::<Runtime>::Unauthorised
+		);
+
+		dispatch_as_multisig(RuntimeCall::Migration(pallet_migration::Call::migrate {
+			for_account: [2; 32],
+			account_to_credit: bob(),
+			amount: 100,
+			source_ref: None,
+			category: None,
+		}));
+
+		assert_eq!(Assets::balance(TOKEN_ID, bob()), 100);
+	});
+}
+
+#[test]
+fn migration_proxy_of_multisig_owner_can_migrate() {
+	new_test_ext_with_owner(multisig()).execute_with(|| {
+		dispatch_as_multisig(RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+			delegate: charlie().into(),
+			proxy_type: ProxyType::Migration,
+			delay: 0,
+		}));
+
+		let migrate = RuntimeCall::Migration(pallet_migration::Call::migrate {
+			for_account: [2; 32],
+			account_to_credit: bob(),
+			amount: 100,
+			source_ref: None,
+			category: None,
+		});
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(charlie()), multisig().into(), None, Box::new(migrate)));
+		assert_eq!(Assets::balance(TOKEN_ID, bob()), 100);
+
+		// Anything outside the migration pallet is filtered for the proxy.
+		let transfer = RuntimeCall::Balances(pallet_balances::Call::transfer { dest: charlie().into(), value: 1 });
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(charlie()), multisig().into(), None, Box::new(transfer)));
+		System::assert_last_event(RuntimeEvent::Proxy(pallet_proxy::Event::ProxyExecuted {
+			result: Err(frame_system::Error::<Runtime>::CallFiltered.into()),
+		}));
+		assert_eq!(Balances::free_balance(charlie()), 0);
+	});
+}
+
+#[test]
+fn delayed_migration_proxy_has_to_announce_migrations() {
+	new_test_ext_with_owner(multisig()).execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&charlie(), 1 << 40);
+		dispatch_as_multisig(RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+			delegate: charlie().into(),
+			proxy_type: ProxyType::Migration,
+			delay: 10,
+		}));
+
+		let migrate = RuntimeCall::Migration(pallet_migration::Call::migrate {
+			for_account: [2; 32],
+			account_to_credit: bob(),
+			amount: 100,
+			source_ref: None,
+			category: None,
+		});
+		assert_noop!(
+			Proxy::proxy(RuntimeOrigin::signed(charlie()), multisig().into(), None, Box::new(migrate.clone())),
+			pallet_proxy::Error::<Runtime>::Unannounced
+		);
+
+		assert_ok!(Proxy::announce(RuntimeOrigin::signed(charlie()), multisig().into(), BlakeTwo256::hash_of(&migrate)));
+		System::set_block_number(10);
+		assert_noop!(
+			Proxy::proxy_announced(RuntimeOrigin::signed(alice()), charlie().into(), multisig().into(), None, Box::new(migrate.clone())),
+			pallet_proxy::Error::<Runtime>::Unannounced
+		);
+		assert_eq!(Assets::balance(TOKEN_ID, bob()), 0);
+
+		// The owner has the length of the delay to notice the announcement and remove the proxy.
+		System::set_block_number(11);
+		assert_ok!(Proxy::proxy_announced(RuntimeOrigin::signed(alice()), charlie().into(), multisig().into(), None, Box::new(migrate)));
+		System::assert_last_event(RuntimeEvent::Proxy(pallet_proxy::Event::ProxyExecuted { result: Ok(()) }));
+		assert_eq!(Assets::balance(TOKEN_ID, bob()), 100);
+	});
+}
+
+#[test]
+fn fees_are_split_between_treasury_and_author() {
+	new_test_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+		let pot = Balances::free_balance(Treasury::account_id());
+		System::deposit_log(DigestItem::PreRuntime(sp_consensus_aura::AURA_ENGINE_ID, sp_consensus_aura::Slot::from(0).encode()));
+
+		let fee = Balances::issue(1_000);
+		let tip = Balances::issue(100);
+		DealWithFees::on_unbalanceds(vec![fee, tip].into_iter());
+
+		assert_eq!(Balances::free_balance(Treasury::account_id()), pot + 800);
+		assert_eq!(Balances::free_balance(author()), 300);
+		assert_eq!(Balances::total_issuance(), issuance + 1_100);
+	});
+}
+
+#[test]
+fn fees_go_to_treasury_without_author() {
+	new_test_ext().execute_with(|| {
+		let pot = Balances::free_balance(Treasury::account_id());
+		DealWithFees::on_unbalanceds(vec![Balances::issue(1_000), Balances::issue(100)].into_iter());
+
+		assert_eq!(Balances::free_balance(Treasury::account_id()), pot + 1_100);
+	});
+}
+
+#[test]
+fn asset_fees_fall_back_to_treasury_or_are_burned() {
+	new_test_ext().execute_with(|| {
+		System::deposit_log(DigestItem::PreRuntime(sp_consensus_aura::AURA_ENGINE_ID, sp_consensus_aura::Slot::from(0).encode()));
+		// Fees below the minimum balance can't open an account for the author.
+		assert_ok!(Assets::force_asset_status(
+			RuntimeOrigin::root(),
+			TOKEN_ID.into(),
+			Migration::account_id().into(),
+			Migration::account_id().into(),
+			Migration::account_id().into(),
+			Migration::account_id().into(),
+			100,
+			true,
+			false,
+		));
+
+		// Neither the author nor the treasury can take it, and the vault never does.
+		AssetFeesToAuthor::handle_credit(<Assets as Balanced<AccountId>>::issue(TOKEN_ID, 10));
+		assert_eq!(Assets::balance(TOKEN_ID, author()), 0);
+		assert_eq!(Assets::balance(TOKEN_ID, Treasury::account_id()), 0);
+		assert_eq!(Assets::balance(TOKEN_ID, vault()), VAULT_TOTAL);
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(vault()), TOKEN_ID.into(), Treasury::account_id().into(), 100));
+		AssetFeesToAuthor::handle_credit(<Assets as Balanced<AccountId>>::issue(TOKEN_ID, 10));
+		assert_eq!(Assets::balance(TOKEN_ID, Treasury::account_id()), 110);
+		assert_eq!(Assets::balance(TOKEN_ID, vault()), VAULT_TOTAL - 100);
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL + 10);
+	});
+}
+
+#[test]
+fn council_refills_gas_accounts_from_treasury() {
+	new_test_ext().execute_with(|| {
+		Treasury::on_unbalanced(Balances::issue(1_000));
+		let pot = Balances::free_balance(Treasury::account_id());
+
+		let spend = RuntimeCall::Treasury(pallet_treasury::Call::spend { amount: 400, beneficiary: charlie().into() });
+		assert_noop!(spend.clone().dispatch(RuntimeOrigin::signed(alice())), DispatchError::BadOrigin);
+		pass_council_motion(alice(), &[bob()], spend);
+
+		// Approved spends are paid out at the end of the spend period.
+		Treasury::spend_funds();
+		assert_eq!(Balances::free_balance(charlie()), 400);
+		assert_eq!(Balances::free_balance(Treasury::account_id()), pot - 400);
+	});
+}
+
+#[test]
+fn asset_transfers_and_fees_are_weighted_with_the_freezer_query() {
+	let freezer = <Migration as pallet_migration::FreezerWeight>::frozen_balance();
+	assert_eq!(freezer, RocksDbWeight::get().reads(6 + 2 * 16));
+
+	let call = RuntimeCall::Assets(pallet_assets::Call::transfer { id: TOKEN_ID.into(), target: bob().into(), amount: 10 });
+	assert_eq!(
+		call.get_dispatch_info().weight,
+		<pallet_assets::weights::SubstrateWeight<Runtime> as pallet_assets::WeightInfo>::transfer().saturating_add(freezer)
+	);
+	let mint = RuntimeCall::Assets(pallet_assets::Call::mint { id: TOKEN_ID.into(), beneficiary: bob().into(), amount: 10 });
+	assert_eq!(mint.get_dispatch_info().weight, <pallet_assets::weights::SubstrateWeight<Runtime> as pallet_assets::WeightInfo>::mint());
+
+	let base_extrinsic = BlockWeights::get().get(frame_support::dispatch::DispatchClass::Normal).base_extrinsic;
+	assert_eq!(base_extrinsic, ExtrinsicBaseWeight::get().saturating_add(freezer));
+}
+
+#[test]
+fn fees_can_be_paid_in_the_migrated_asset() {
+	new_test_ext().execute_with(|| {
+		// Charlie only holds migrated tokens, no GAS.
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(alice()), [4; 32], charlie(), 100_000, None, None));
+		assert_eq!(Balances::free_balance(charlie()), 0);
+
+		let call = RuntimeCall::Assets(pallet_assets::Call::transfer { id: TOKEN_ID.into(), target: bob().into(), amount: 10 });
+		let info = call.get_dispatch_info();
+		let len = call.encoded_size();
+		let charge = || ChargeAssetTxPayment::<Runtime>::from(0, Some(TOKEN_ID));
+
+		// Not possible until the council sets a rate.
+		assert!(charge().pre_dispatch(&charlie(), &call, &info, len).is_err());
+		assert_ok!(Migration::set_fee_rate(RuntimeOrigin::root(), Some(FixedU128::from_rational(1, 1_000_000_000))));
+
+		let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+		let asset_fee = Migration::to_asset_balance(fee, TOKEN_ID).unwrap().max(1);
+		let treasury_before = Assets::balance(TOKEN_ID, Treasury::account_id());
+
+		let pre = charge().pre_dispatch(&charlie(), &call, &info, len).unwrap();
+		assert_ok!(call.dispatch(RuntimeOrigin::signed(charlie())));
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(Some(pre), &info, &Default::default(), len, &Ok(())));
+
+		assert_eq!(Assets::balance(TOKEN_ID, charlie()), 100_000 - 10 - asset_fee);
+		// Without a block author the fee goes to the treasury, it is not burned.
+		assert_eq!(Assets::balance(TOKEN_ID, Treasury::account_id()), treasury_before + asset_fee);
+		assert_eq!(Assets::total_supply(TOKEN_ID), VAULT_TOTAL);
+	});
+}
+
+#[test]
+fn fees_cannot_be_paid_in_other_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create(RuntimeOrigin::signed(bob()), OTHER_TOKEN_ID.into(), bob().into(), 1));
+		assert_ok!(Migration::set_fee_rate(RuntimeOrigin::root(), Some(FixedU128::from_rational(1, 1_000_000_000))));
+
+		let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+		let info = call.get_dispatch_info();
+		assert!(ChargeAssetTxPayment::<Runtime>::from(0, Some(OTHER_TOKEN_ID))
+			.pre_dispatch(&bob(), &call, &info, call.encoded_size())
+			.is_err());
+	});
+}
+
+#[test]
+fn sponsor_pays_gas_of_migrated_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Migration::migrate(RuntimeOrigin::signed(alice()), [4; 32], charlie(), 1_000, None, None));
+		assert_ok!(Migration::add_sponsor(RuntimeOrigin::root(), bob()));
+		let assets_index = <Assets as frame_support::traits::PalletInfoAccess>::index() as u8;
+		assert_ok!(Migration::sponsor(
+			RuntimeOrigin::signed(bob()),
+			charlie(),
+			1 << 40,
+			vec![assets_index].try_into().unwrap(),
+		));
+
+		let call = RuntimeCall::Assets(pallet_assets::Call::transfer { id: TOKEN_ID.into(), target: alice().into(), amount: 10 });
+		let info = call.get_dispatch_info();
+		let len = call.encoded_size();
+		let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+		let bob_before = Balances::free_balance(bob());
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None).pre_dispatch(&charlie(), &call, &info, len).unwrap();
+		assert_ok!(call.dispatch(RuntimeOrigin::signed(charlie())));
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(Some(pre), &info, &Default::default(), len, &Ok(())));
+
+		assert_eq!(Balances::free_balance(bob()), bob_before - fee);
+		assert_eq!(Balances::free_balance(charlie()), 0);
+		assert_eq!(Assets::balance(TOKEN_ID, alice()), 10);
+	});
+}
+
+/// Sponsors `charlie` for calls to `Assets`, and gives it `1 << 40` to pay other fees with.
+fn sponsor_charlie_for_assets() {
+	assert_ok!(Migration::migrate(RuntimeOrigin::signed(alice()), [4; 32], charlie(), 1_000, None, None));
+	assert_ok!(Migration::add_sponsor(RuntimeOrigin::root(), bob()));
+	let assets_index = <Assets as frame_support::traits::PalletInfoAccess>::index() as u8;
+	assert_ok!(Migration::sponsor(RuntimeOrigin::signed(bob()), charlie(), 1 << 40, vec![assets_index].try_into().unwrap()));
+	Balances::make_free_balance_be(&charlie(), 1 << 40);
+}
+
+#[test]
+fn wrapped_calls_of_sponsored_pallets_are_not_sponsored() {
+	new_test_ext().execute_with(|| {
+		sponsor_charlie_for_assets();
+		let transfer = RuntimeCall::Assets(pallet_assets::Call::transfer { id: TOKEN_ID.into(), target: alice().into(), amount: 10 });
+		let call = RuntimeCall::Proxy(pallet_proxy::Call::proxy { real: alice().into(), force_proxy_type: None, call: Box::new(transfer) });
+		let info = call.get_dispatch_info();
+		let len = call.encoded_size();
+		let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+		let bob_before = Balances::free_balance(bob());
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::from(0, None).pre_dispatch(&charlie(), &call, &info, len));
+
+		// Only the pallet of the outer call counts, and `Proxy` isn't sponsored.
+		assert_eq!(Balances::free_balance(bob()), bob_before);
+		assert_eq!(Balances::free_balance(charlie()), (1 << 40) - fee);
+		assert_eq!(Migration::sponsorship(charlie()).unwrap().remaining, 1 << 40);
+	});
+}
+
+#[test]
+fn sender_pays_when_its_sponsor_cannot() {
+	new_test_ext().execute_with(|| {
+		sponsor_charlie_for_assets();
+		Balances::make_free_balance_be(&bob(), EXISTENTIAL_DEPOSIT);
+		let call = RuntimeCall::Assets(pallet_assets::Call::transfer { id: TOKEN_ID.into(), target: alice().into(), amount: 10 });
+		let info = call.get_dispatch_info();
+		let len = call.encoded_size();
+		let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None).pre_dispatch(&charlie(), &call, &info, len).unwrap();
+		assert_ok!(call.dispatch(RuntimeOrigin::signed(charlie())));
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(Some(pre), &info, &Default::default(), len, &Ok(())));
+
+		assert_eq!(Balances::free_balance(bob()), EXISTENTIAL_DEPOSIT);
+		assert_eq!(Balances::free_balance(charlie()), (1 << 40) - fee);
+		assert_eq!(Migration::sponsorship(charlie()).unwrap().remaining, 1 << 40);
+	});
+}